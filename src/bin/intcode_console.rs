@@ -0,0 +1,364 @@
+// A small console to poke at any Intcode program without writing a new module for it.
+// Lines are edited with the cursor keys and the up/down keys go through everything entered before, see line_editor.
+
+use adventofcode_2019::intcode_ascii::*;
+use adventofcode_2019::intcode_computer::*;
+use adventofcode_2019::intcode_debugger::*;
+use adventofcode_2019::intcode_inspect::*;
+use adventofcode_2019::intcode_loader::*;
+use adventofcode_2019::intcode_patch::*;
+use adventofcode_2019::intcode_trace::*;
+use adventofcode_2019::line_editor::*;
+use std::io::Write;
+
+#[derive(PartialEq, Clone, Copy)]
+enum Mode {
+    Numeric,
+    Ascii
+}
+
+fn usage() {
+    println!("Useage: {} <intcode file (text, or binary if it ends with .bin/.icb)> [--ascii | --numeric | --patch <file>... | --trace <file> | --record <file> | --serve <port> | --serve-unix <path>]", std::env::args().next().unwrap());
+    println!("  --patch applies a memory patch file (lines of \"address = value\" or \"address: values\") after loading");
+    println!("  --trace writes every executed instruction to the file, as CSV if it ends with .csv and as JSON Lines otherwise");
+    println!("  --record writes the inputs and outputs of the session to the file on exit, to replay it in a test later");
+    println!("  --serve and --serve-unix expose the program to a remote debugger instead of the console");
+    println!("Lines are edited with the cursor keys, Home/End, Ctrl-U and Ctrl-W, and Up/Down go through the history.");
+    println!("Earlier lines can also be repeated with !! and !N, see :help.");
+}
+
+fn help() {
+    println!("Commands:");
+    println!("  :mode ascii|numeric  Switch how input lines are sent to the program");
+    println!("  :history             List the previously entered lines");
+    println!("  :dump [hex]          Show the memory");
+    println!("  :diff                Show what changed in the memory since the last input");
+    println!("  :observe <value>     Remember the memory along with a value seen in the output, like the score");
+    println!("  :cells [threshold]   List the cells that held the observed values, then the ones whose values");
+    println!("                       correlate with them by at least the threshold (0.9 by default), to find patches");
+    println!("  !!                   Repeat the last line");
+    println!("  !N                   Repeat line N from the history");
+    println!("  :help                Show this help");
+    println!("  :quit                Exit the console");
+    println!("In numeric mode a line may hold several values separated by commas or spaces.");
+}
+
+struct History {
+    lines: Vec<String>
+}
+
+impl History {
+    /// Expands !! and !N references into the line they refer to
+    fn expand(&self, line: &str) -> Result<String, String> {
+        if line == "!!" {
+            return self.lines.last().cloned().ok_or_else(|| String::from("History is empty"));
+        }
+
+        if let Some(reference) = line.strip_prefix('!') {
+            let index = reference.parse::<usize>().map_err(|_| format!("Invalid history reference: {}", line))?;
+            return self.lines.get(index.wrapping_sub(1)).cloned().ok_or_else(|| format!("No history entry {}", index));
+        }
+
+        Ok(String::from(line))
+    }
+
+    fn push(&mut self, line: &str) {
+        self.lines.push(String::from(line));
+    }
+
+    fn print(&self) {
+        for (i, line) in self.lines.iter().enumerate() {
+            println!("{:4}  {}", i + 1, line);
+        }
+    }
+}
+
+fn print_output(computer: &mut IntCodeComputer, mode: Mode) {
+    let output = computer.pop_all_output();
+
+    match mode {
+        Mode::Ascii => {
+            let output = split_output(&output);
+            print!("{}", output.text);
+            for value in output.values {
+                println!("{}", value);
+            }
+        },
+        Mode::Numeric => {
+            for value in output {
+                println!("{}", value);
+            }
+        }
+    }
+}
+
+fn print_cells(search: &CellSearch, threshold: f64) {
+    println!("Holding the observed values: {:?}", search.matching_cells());
+    for (address, correlation) in search.correlated_cells(threshold) {
+        println!("{:6}  {:+.3}", address, correlation);
+    }
+}
+
+fn send_input(console: &mut AsciiComputer, line: &str, mode: Mode) -> Result<(), String> {
+    match mode {
+        Mode::Ascii => console.send_line(line),
+        Mode::Numeric => {
+            let values = line.split(|c: char| c == ',' || c.is_whitespace())
+                             .filter(|item| !item.is_empty())
+                             .map(|item| item.parse::<isize>().map_err(|_| format!("Expected an integer, got: {}", item)))
+                             .collect::<Result<Vec<isize>, String>>()?;
+
+            for value in values {
+                console.computer_mut().push_input(value);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let mut mode = Mode::Numeric;
+    let mut path = None;
+    let mut patch_paths = Vec::new();
+    let mut trace_path = None;
+    let mut record_path = None;
+    let mut serve_port = None;
+    let mut serve_path = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_ref() {
+            "--ascii" => mode = Mode::Ascii,
+            "--numeric" => mode = Mode::Numeric,
+            "--patch" => match args.next() {
+                Some(patch_path) => patch_paths.push(patch_path.clone()),
+                None => {
+                    usage();
+                    return;
+                }
+            },
+            "--trace" => match args.next() {
+                Some(path) => trace_path = Some(path.clone()),
+                None => {
+                    usage();
+                    return;
+                }
+            },
+"--record" => match args.next() {
+                Some(path) => record_path = Some(path.clone()),
+                None => {
+                    usage();
+                    return;
+                }
+            },
+            "--serve" => match args.next().map(|port| (port, port.parse::<u16>())) {
+                Some((_, Ok(port))) => serve_port = Some(port),
+                Some((port, Err(_))) => {
+                    println!("Invalid port: {}", port);
+                    usage();
+                    return;
+                },
+                None => {
+                    usage();
+                    return;
+                }
+            },
+            "--serve-unix" => match args.next() {
+                Some(path) => serve_path = Some(path.clone()),
+                None => {
+                    usage();
+                    return;
+                }
+            },
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg.clone()),
+            _ => {
+                usage();
+                return;
+            }
+        }
+    }
+
+    let path = match path {
+        Some(path) => path,
+        None => {
+            usage();
+            return;
+        }
+    };
+
+    // The remote debugger runs the program itself, there is nothing for the tracer or the recording to see
+    if (trace_path.is_some() || record_path.is_some()) && (serve_port.is_some() || serve_path.is_some()) {
+        println!("--trace and --record can not be combined with --serve or --serve-unix");
+        usage();
+        return;
+    }
+
+    let program = match load_program(&path) {
+        Ok(program) => program,
+        Err(error) => {
+            println!("Unable to load {}: {}", path, error);
+            return;
+        }
+    };
+
+    if let Some(name) = program.name.as_ref() {
+        println!("Loaded {}", name);
+    }
+
+    if let Some(inputs) = program.expected_inputs {
+        println!("The program expects {} inputs", inputs);
+    }
+
+    let mut computer = IntCodeComputer::from(program);
+
+    for patch_path in patch_paths.iter() {
+        let patch = match std::fs::read_to_string(patch_path) {
+            Ok(text) => Patch::parse(&text).map_err(|error| error.to_string()),
+            Err(error) => Err(error.to_string())
+        };
+
+        match patch {
+            Ok(patch) => patch.apply(&mut computer),
+            Err(message) => {
+                println!("Unable to load the patch {}: {}", patch_path, message);
+                return;
+            }
+        }
+    }
+
+    if let Some(port) = serve_port {
+        println!("Serving {} on 127.0.0.1:{}", path, port);
+        serve_tcp(&mut DebugSession::with_computer(computer), port).expect("Unable to serve the debug session");
+        return;
+    }
+
+    #[cfg(unix)]
+    if let Some(socket_path) = serve_path {
+        println!("Serving {} on {}", path, socket_path);
+        serve_unix(&mut DebugSession::with_computer(computer), &socket_path).expect("Unable to serve the debug session");
+        return;
+    }
+
+    #[cfg(not(unix))]
+    if serve_path.is_some() {
+        println!("--serve-unix needs unix domain sockets, which this platform doesn't have");
+        return;
+    }
+
+    let mut tracer = match trace_path {
+        Some(trace_path) => match std::fs::File::create(&trace_path) {
+            Ok(file) => {
+                let writer = std::io::BufWriter::new(file);
+                let sink: Box<dyn TraceSink> = if trace_path.ends_with(".csv") { Box::new(CsvSink::new(writer)) }
+                                               else { Box::new(JsonLinesSink::new(writer)) };
+                Some(Tracer::new(sink))
+            },
+            Err(error) => {
+                println!("Unable to create {}: {}", trace_path, error);
+                return;
+            }
+        },
+        None => None
+    };
+
+    // Created right away, so that a bad path doesn't only show up once the session is over
+    let record_file = match record_path.as_ref().map(std::fs::File::create) {
+        Some(Ok(file)) => {
+            computer.start_recording();
+            Some(file)
+        },
+        Some(Err(error)) => {
+            println!("Unable to create {}: {}", record_path.unwrap(), error);
+            return;
+        },
+        None => None
+    };
+
+    let mut history = History { lines: Vec::new() };
+    let mut console = AsciiComputer::new(computer);
+    let mut previous_computer = console.computer().clone();
+    let mut cell_search = CellSearch::new();
+    let mut editor = LineEditor::new();
+
+    loop {
+        match tracer.as_mut() {
+            Some(tracer) => {
+                tracer.process(console.computer_mut(), ReturnEvent::InputNeededEvent).expect("Unable to write the trace");
+            },
+            None => {
+                console.computer_mut().process(ReturnEvent::InputNeededEvent);
+            }
+        }
+        print_output(console.computer_mut(), mode);
+
+        if console.is_halted() {
+            println!("Program halted.");
+            break;
+        }
+
+        let prompt = if mode == Mode::Ascii { "> " } else { "# " };
+        let line = match editor.read_line(prompt, &history.lines).expect("Unable to read from stdin") {
+            Some(line) => line,
+            None => break
+        };
+
+        let line = match history.expand(line.trim_end()) {
+            Ok(line) => line,
+            Err(message) => {
+                println!("{}", message);
+                continue;
+            }
+        };
+
+        match line.trim() {
+            ":quit" => break,
+            ":help" => help(),
+            ":history" => history.print(),
+            ":mode ascii" => mode = Mode::Ascii,
+            ":mode numeric" => mode = Mode::Numeric,
+            ":dump" => println!("{}", dump_memory(console.computer(), DumpFormat::Decimal, 10)),
+            ":dump hex" => println!("{}", dump_memory(console.computer(), DumpFormat::Hex, 10)),
+            ":diff" => println!("{}", describe_diff(&previous_computer, console.computer())),
+            command if command.starts_with(":observe ") => match command[":observe ".len()..].trim().parse::<isize>() {
+                Ok(value) => cell_search.observe(console.computer(), value),
+                Err(_) => {
+                    println!("Expected :observe <value>");
+                    continue;
+                }
+            },
+            command if command == ":cells" || command.starts_with(":cells ") => {
+                let threshold = command[":cells".len()..].trim();
+                match if threshold.is_empty() { Ok(0.9) } else { threshold.parse::<f64>() } {
+                    Ok(threshold) => print_cells(&cell_search, threshold),
+                    Err(_) => {
+                        println!("Expected :cells [threshold]");
+                        continue;
+                    }
+                }
+            },
+            command if command.starts_with(':') => {
+                println!("Unknown command: {}", command);
+                help();
+                continue;
+            },
+            _ => {
+                previous_computer = console.computer().clone();
+                if let Err(message) = send_input(&mut console, &line, mode) {
+                    println!("{}", message);
+                    continue;
+                }
+            }
+        }
+
+        history.push(&line);
+    }
+
+    if let Some(mut file) = record_file {
+        let recording = console.computer_mut().take_recording().unwrap_or_default();
+        if let Err(error) = write!(file, "{}", recording) {
+            println!("Unable to write the recording: {}", error);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+// The answers we know to be right, so that a refactoring that changes one doesn't go unnoticed.
+//
+// They are kept in testdata/answers.toml, as one [[answer]] table per day, part and input.
+// The input is identified by its hash, so that everyone's own puzzle input can have its answers in there:
+//   [[answer]]
+//   day = 1
+//   part = "a"
+//   input_hash = "d974e7015bb15239"
+//   answer = "3405637"
+//
+// Only that much of TOML is understood, anything else is rejected with an error saying so:
+//   - [[answer]] table headers, no other tables
+//   - bare keys (letters, digits, _ and -), each at most once per table
+//   - non-negative integers, and basic "strings" with the \n, \t, \" and \\ escapes.
+//     No 'literal' or multi-line strings, arrays, inline tables, booleans, floats or dates
+//   - # comments, on their own line or after a value
+
+use super::parse_error::*;
+use super::solution::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub day: usize,
+    pub part: Part,
+    pub input_hash: String,
+    pub answer: String
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown
+}
+
+enum Value {
+    Integer(usize),
+    String(String)
+}
+
+// (key, value, line)
+type Field = (String, Value, usize);
+
+// Parses a value starting at column (1 based), returns it with the rest of the line
+fn parse_value(text: &str, line: usize, column: usize) -> Result<(Value, &str), ParseError> {
+    if text.starts_with("\"\"\"") || text.starts_with('\'') {
+        return Err(ParseError::new(line, column, "Only basic \"strings\" are supported, write line breaks as \\n"));
+    }
+
+    if let Some(text) = text.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = text.char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Ok((Value::String(value), &text[i + 1..])),
+                '\\' => value.push(match chars.next() {
+                    Some((_, 'n')) => '\n',
+                    Some((_, 't')) => '\t',
+                    Some((_, '"')) => '"',
+                    Some((_, '\\')) => '\\',
+                    _ => return Err(ParseError::new(line, column + i + 1, "Unsupported escape sequence, only \\n, \\t, \\\" and \\\\ are"))
+                }),
+                _ => value.push(c)
+            }
+        }
+
+        Err(ParseError::new(line, column, "Unterminated string"))
+    } else {
+        let end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+        let value = text[..end].parse::<usize>()
+                               .map_err(|_| ParseError::new(line, column, "Expected an integer or a \"string\", other values are not supported"))?;
+        Ok((Value::Integer(value), &text[end..]))
+    }
+}
+
+fn finish_answer(fields: &mut Vec<Field>, line: usize) -> Result<Answer, ParseError> {
+    let mut day = None;
+    let mut part = None;
+    let mut input_hash = None;
+    let mut answer = None;
+
+    for (key, value, key_line) in fields.drain(..) {
+        match (key.as_ref(), value) {
+            ("day", Value::Integer(value)) => day = Some(value),
+            ("part", Value::String(ref value)) if value == "a" => part = Some(Part::A),
+            ("part", Value::String(ref value)) if value == "b" => part = Some(Part::B),
+            ("input_hash", Value::String(value)) => input_hash = Some(value),
+            ("answer", Value::String(value)) => answer = Some(value),
+            (key, _) => return Err(ParseError::new(key_line, 1, &format!("Unexpected value for {}", key)))
+        }
+    }
+
+    match (day, part, input_hash, answer) {
+        (Some(day), Some(part), Some(input_hash), Some(answer)) => Ok(Answer { day, part, input_hash, answer }),
+        _ => Err(ParseError::new(line, 1, "An answer needs a day, a part, an input_hash and an answer"))
+    }
+}
+
+pub fn parse_answers(text: &str) -> Result<Vec<Answer>, ParseError> {
+    let mut answers = Vec::new();
+    // The fields of the table being read and the line it started at
+    let mut table: Option<(Vec<Field>, usize)> = None;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let code = line.trim_start();
+        let indent = line.len() - code.len();
+
+        if code.is_empty() || code.starts_with('#') {
+            continue;
+        }
+
+        if code.starts_with('[') && code.trim_end() != "[[answer]]" {
+            return Err(ParseError::new(line_number, indent + 1, &format!("Only [[answer]] tables are supported, got: {}", code.trim_end())));
+        }
+
+        if code.trim_end() == "[[answer]]" {
+            if let Some((mut fields, start)) = table.take() {
+                answers.push(finish_answer(&mut fields, start)?);
+            }
+            table = Some((Vec::new(), line_number));
+            continue;
+        }
+
+        let (fields, _) = table.as_mut().ok_or_else(|| ParseError::new(line_number, indent + 1, "Expected [[answer]]"))?;
+        let separator = code.find('=').ok_or_else(|| ParseError::new(line_number, indent + 1, "Expected key = value"))?;
+        let key = code[..separator].trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(ParseError::new(line_number, indent + 1, &format!("Only bare keys are supported, got: {}", key)));
+        }
+        if fields.iter().any(|(k, _, _)| k == key) {
+            return Err(ParseError::new(line_number, indent + 1, &format!("Duplicate key {}", key)));
+        }
+        let value_text = code[separator + 1..].trim_start();
+        let value_column = line.len() - value_text.len() + 1;
+        let (value, rest) = parse_value(value_text, line_number, value_column)?;
+
+        let rest = rest.trim_start();
+        if !rest.is_empty() && !rest.starts_with('#') {
+            return Err(ParseError::new(line_number, line.len() - rest.len() + 1, "Unexpected text after the value"));
+        }
+
+        fields.push((String::from(key), value, line_number));
+    }
+
+    if let Some((mut fields, start)) = table.take() {
+        answers.push(finish_answer(&mut fields, start)?);
+    }
+
+    Ok(answers)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+/// The [[answer]] table for the answer, as it goes in the file
+pub fn format_answer(answer: &Answer) -> String {
+    format!("[[answer]]\nday = {}\npart = \"{}\"\ninput_hash = \"{}\"\nanswer = \"{}\"\n",
+            answer.day, answer.part, answer.input_hash, escape(&answer.answer))
+}
+
+pub fn check(answers: &[Answer], day: usize, part: Part, input_hash: &str, actual: &str) -> Verdict {
+    match answers.iter().find(|answer| answer.day == day && answer.part == part && answer.input_hash == input_hash) {
+        Some(answer) if answer.answer == actual => Verdict::Pass,
+        Some(answer) => Verdict::Fail { expected: answer.answer.clone() },
+        None => Verdict::Unknown
+    }
+}
+
+/// The lines that differ between the expected and the actual answer, for multi line answers like day 8's image
+pub fn describe_diff(expected: &str, actual: &str) -> String {
+    let expected_lines = expected.lines().collect::<Vec<&str>>();
+    let actual_lines = actual.lines().collect::<Vec<&str>>();
+
+    (0..std::cmp::max(expected_lines.len(), actual_lines.len())).filter_map(|i| {
+                                                                    match (expected_lines.get(i), actual_lines.get(i)) {
+                                                                        (e, a) if e == a => None,
+                                                                        (e, a) => Some(format!("- {}\n+ {}", e.unwrap_or(&""), a.unwrap_or(&"")))
+                                                                    }
+                                                                })
+                                                                .collect::<Vec<String>>()
+                                                                .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answer = Answer { day: 8, part: Part::B, input_hash: String::from("00ff"), answer: String::from("\n# \"x\"\n ##") };
+        let text = format!("# Known answers\n\n{}\n[[answer]]\nday = 1  # comment\npart = \"a\"\ninput_hash = \"00ff\"\nanswer = \"42\"\n", format_answer(&answer));
+        let answers = parse_answers(&text).unwrap();
+
+        assert_eq!(answers, [answer, Answer { day: 1, part: Part::A, input_hash: String::from("00ff"), answer: String::from("42") }]);
+        assert_eq!(check(&answers, 1, Part::A, "00ff", "42"), Verdict::Pass);
+        assert_eq!(check(&answers, 1, Part::A, "00ff", "43"), Verdict::Fail { expected: String::from("42") });
+        assert_eq!(check(&answers, 1, Part::A, "0123", "42"), Verdict::Unknown);
+
+        assert_eq!(parse_answers("[[answer]]\nday = 1\npart = \"c\""), Err(ParseError::new(3, 1, "Unexpected value for part")));
+        assert_eq!(parse_answers("[[answer]]\nday = \"1"), Err(ParseError::new(2, 7, "Unterminated string")));
+        assert_eq!(parse_answers("day = 1"), Err(ParseError::new(1, 1, "Expected [[answer]]")));
+
+        // Valid TOML, but not the subset we understand
+        assert_eq!(parse_answers("[answers]"), Err(ParseError::new(1, 1, "Only [[answer]] tables are supported, got: [answers]")));
+        assert_eq!(parse_answers("[[answer]]\n\"day\" = 1"), Err(ParseError::new(2, 1, "Only bare keys are supported, got: \"day\"")));
+        assert_eq!(parse_answers("[[answer]]\nday = 1\nday = 2"), Err(ParseError::new(3, 1, "Duplicate key day")));
+        assert_eq!(parse_answers("[[answer]]\nanswer = 'x'"),
+                   Err(ParseError::new(2, 10, "Only basic \"strings\" are supported, write line breaks as \\n")));
+        assert_eq!(parse_answers("[[answer]]\nday = true"),
+                   Err(ParseError::new(2, 7, "Expected an integer or a \"string\", other values are not supported")));
+    }
+
+    #[test]
+    fn test_describe_diff() {
+        assert_eq!(describe_diff("# \n##\n #", "# \n# \n #"), "- ##\n+ # ");
+    }
+}
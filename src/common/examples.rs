@@ -0,0 +1,141 @@
+// The worked examples of the problem statements, annotated so that they can be run against each day's solution:
+//   === example a steps=10 ===
+//   <x=-1, y=0, z=2>
+//   ...
+//   === answer ===
+//   179
+//   === end ===
+// The header names the part and any parameters the example needs, as key=value, the way --param takes them.
+// Both the input and the answer are the lines in between, the answer compared without trailing spaces
+// since an editor may well strip them from the statement.
+
+use super::parse_error::*;
+use super::solution::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    /// The header's line in the problem statement
+    pub line: usize,
+    pub part: Part,
+    pub params: Vec<(String, String)>,
+    pub input: String,
+    pub answer: String
+}
+
+enum Block {
+    Input,
+    Answer
+}
+
+fn parse_header(header: &str, line: usize) -> Result<(Part, Vec<(String, String)>), ParseError> {
+    let mut items = header.split_whitespace();
+
+    let part = match items.next() {
+        Some("a") => Part::A,
+        Some("b") => Part::B,
+        _ => return Err(ParseError::new(line, 1, "Expected the part, a or b, after example"))
+    };
+
+    let params = items.map(|item| {
+                          let mut pair = item.splitn(2, '=');
+                          match (pair.next(), pair.next()) {
+                              (Some(key), Some(value)) => Ok((String::from(key), String::from(value))),
+                              _ => Err(ParseError::new(line, 1, &format!("Expected key=value, got: {}", item)))
+                          }
+                      })
+                      .collect::<Result<Vec<(String, String)>, ParseError>>()?;
+
+    Ok((part, params))
+}
+
+/// The annotated examples of a problem statement, the rest of its text is left alone
+pub fn parse_examples(text: &str) -> Result<Vec<Example>, ParseError> {
+    let mut examples = Vec::new();
+    // The example being read, and which of its blocks
+    let mut current: Option<(Example, Block)> = None;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let marker = line.trim()
+                         .strip_prefix("===")
+                         .and_then(|marker| marker.strip_suffix("==="))
+                         .map(|marker| marker.trim());
+
+        match (marker, current.take()) {
+            (Some(header), None) if header.starts_with("example") => {
+                let (part, params) = parse_header(&header["example".len()..], line_number)?;
+                current = Some((Example { line: line_number, part, params, input: String::new(), answer: String::new() }, Block::Input));
+            },
+            // Each line was pushed with its newline, less the last one
+            (Some("answer"), Some((mut example, Block::Input))) => {
+                example.input.pop();
+                current = Some((example, Block::Answer));
+            },
+            (Some("end"), Some((mut example, Block::Answer))) => {
+                example.answer.pop();
+                examples.push(example);
+            },
+            (Some(marker), _) => return Err(ParseError::new(line_number, 1, &format!("Unexpected === {} ===", marker))),
+            (None, Some((mut example, block))) => {
+                let lines = match block {
+                    Block::Input => &mut example.input,
+                    Block::Answer => &mut example.answer
+                };
+                lines.push_str(line);
+                lines.push('\n');
+                current = Some((example, block));
+            },
+            (None, None) => ()
+        }
+    }
+
+    match current {
+        Some((example, _)) => Err(ParseError::new(example.line, 1, "Expected === end === for this example")),
+        None => Ok(examples)
+    }
+}
+
+// Without trailing spaces, nor the blank lines around it
+fn normalize(answer: &str) -> String {
+    answer.lines()
+          .map(|line| line.trim_end())
+          .collect::<Vec<&str>>()
+          .join("\n")
+          .trim_matches('\n')
+          .to_string()
+}
+
+/// Runs the example through the day's solution, the error tells what went wrong
+pub fn check_example(solution: &dyn Runner, example: &Example) -> Result<(), String> {
+    let actual = solution.run(example.input.trim(), example.part, &example.params)
+                         .map_err(|error| format!("Day {} example at line {}: {}", solution.day(), example.line, error))?;
+
+    if normalize(&actual) == normalize(&example.answer) {
+        Ok(())
+    } else {
+        Err(format!("Day {} example at line {}, part {}: expected {}, got {}",
+                    solution.day(), example.line, example.part, example.answer, actual))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_examples() {
+        let text = "For example:\n\n=== example b phases=4 ===\n12345678\n=== answer ===\n\n # \n=== end ===\nThe end.\n";
+
+        assert_eq!(parse_examples(text),
+                   Ok(vec![Example { line: 3,
+                                     part: Part::B,
+                                     params: vec![(String::from("phases"), String::from("4"))],
+                                     input: String::from("12345678"),
+                                     answer: String::from("\n # ") }]));
+        assert_eq!(normalize("\n # \n"), " #");
+
+        assert_eq!(parse_examples("=== example c ===\n"), Err(ParseError::new(1, 1, "Expected the part, a or b, after example")));
+        assert_eq!(parse_examples("=== example a ===\n1\n=== end ==="), Err(ParseError::new(3, 1, "Unexpected === end ===")));
+        assert_eq!(parse_examples("\n=== example a ===\n1\n=== answer ===\n"), Err(ParseError::new(2, 1, "Expected === end === for this example")));
+    }
+}
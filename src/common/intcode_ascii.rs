@@ -0,0 +1,123 @@
+use super::intcode_computer::*;
+
+/// Wraps an IntCodeComputer that talks in ASCII:
+/// It prints character codes and expects newline terminated command lines as input
+#[derive(Debug, Clone)]
+pub struct AsciiComputer {
+    computer: IntCodeComputer
+}
+
+/// Output of an ASCII program, split into the printable text
+/// and the trailing values that are not ASCII characters (usually the puzzle answer)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AsciiOutput {
+    pub text: String,
+    pub values: Vec<isize>
+}
+
+impl AsciiComputer {
+    pub fn new(computer: IntCodeComputer) -> AsciiComputer {
+        AsciiComputer { computer }
+    }
+
+    pub fn computer(&self) -> &IntCodeComputer {
+        &self.computer
+    }
+
+    pub fn computer_mut(&mut self) -> &mut IntCodeComputer {
+        &mut self.computer
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.computer.is_halted()
+    }
+
+    /// Queues the given text as input, followed by a newline
+    pub fn send_line(&mut self, line: &str) {
+        for b in line.bytes() {
+            self.computer.push_input(b as isize);
+        }
+
+        self.computer.push_input('\n' as isize);
+    }
+
+    /// Runs the program until it waits for more input (or halts)
+    /// and returns everything it printed in the meantime
+    pub fn read_until_prompt(&mut self) -> String {
+        self.read_output().text
+    }
+
+    /// Same as read_until_prompt, but keeps the non ASCII values that the program printed after its text
+    pub fn read_output(&mut self) -> AsciiOutput {
+        self.computer.process(ReturnEvent::InputNeededEvent);
+        split_output(&self.computer.pop_all_output())
+    }
+}
+
+impl From<&str> for AsciiComputer {
+    fn from(text: &str) -> Self {
+        AsciiComputer::new(IntCodeComputer::from(text))
+    }
+}
+
+impl From<IntCodeComputer> for AsciiComputer {
+    fn from(computer: IntCodeComputer) -> Self {
+        AsciiComputer::new(computer)
+    }
+}
+
+fn is_ascii(value: isize) -> bool {
+    (0..128).contains(&value)
+}
+
+/// Splits the raw output into text and the trailing run of non ASCII values.
+/// Non ASCII values in the middle of the text are kept in the text as "[value]"
+/// so that nothing the program printed is silently dropped.
+pub fn split_output(output: &[isize]) -> AsciiOutput {
+    let trailing_start = output.iter()
+                               .rposition(|&v| is_ascii(v))
+                               .map_or(0, |i| i + 1);
+
+    let text = output[..trailing_start].iter()
+                                       .map(|&v| if is_ascii(v) { (v as u8 as char).to_string() }
+                                                 else { format!("[{}]", v) })
+                                       .collect::<String>();
+
+    AsciiOutput {
+        text,
+        values: output[trailing_start..].to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Prints "?\n", reads one line and echoes it back followed by 1000 + its length
+    const ECHO_PROGRAM: &str = "104,63,104,10,3,100,1008,100,10,101,1005,101,25,4,100,1001,102,1,102,\
+                                1105,1,4,0,0,0,104,10,1001,102,1000,103,4,103,99";
+
+    #[test]
+    fn test_send_line_and_read_until_prompt() {
+        let mut computer = AsciiComputer::from(ECHO_PROGRAM);
+
+        assert_eq!(computer.read_until_prompt(), "?\n");
+
+        computer.send_line("hi");
+        let output = computer.read_output();
+        assert_eq!(output.text, "hi\n");
+        assert_eq!(output.values, [1002]);
+        assert!(computer.is_halted());
+    }
+
+    #[test]
+    fn test_split_output() {
+        assert_eq!(split_output(&[72, 105, 10, 19690720]),
+                   AsciiOutput { text: String::from("Hi\n"), values: vec![19690720] });
+
+        assert_eq!(split_output(&[400, 10, 500, 600]),
+                   AsciiOutput { text: String::from("[400]\n"), values: vec![500, 600] });
+
+        assert_eq!(split_output(&[]), AsciiOutput::default());
+    }
+}
@@ -0,0 +1,252 @@
+// Runs IntCodeComputers as async tasks, so that many of them can be wired together on a single thread.
+// Awaiting an input suspends the computer's task until some other task sends a value into its channel.
+// Everything here is built on std futures, with a minimal executor to drive them.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+use super::intcode_computer::*;
+
+/// Same idea as futures::Stream, which std doesn't have yet
+pub trait Stream {
+    type Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>>;
+}
+
+/// Future that resolves to the next item of a stream
+pub struct Next<'a, S: Stream + Unpin> {
+    stream: &'a mut S
+}
+
+impl<'a, S: Stream + Unpin> Future for Next<'a, S> {
+    type Output = Option<S::Item>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut *self.stream).poll_next(cx)
+    }
+}
+
+pub fn next<S: Stream + Unpin>(stream: &mut S) -> Next<'_, S> {
+    Next { stream }
+}
+
+#[derive(Default)]
+struct ChannelState {
+    values: VecDeque<isize>,
+    is_closed: bool,
+    waker: Option<Waker>
+}
+
+/// A single threaded queue of values between tasks.
+/// Clones share the same queue, so one clone can be handed to the sender and another to the receiver.
+#[derive(Clone, Default)]
+pub struct Channel {
+    state: Rc<RefCell<ChannelState>>
+}
+
+impl Channel {
+    pub fn new() -> Channel {
+        Channel::default()
+    }
+
+    pub fn send(&self, value: isize) {
+        let mut state = self.state.borrow_mut();
+        state.values.push_back(value);
+
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Marks that no more values will be sent. Receivers get None once the queue is drained.
+    pub fn close(&self) {
+        let mut state = self.state.borrow_mut();
+        state.is_closed = true;
+
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Takes a value without waiting for one
+    pub fn try_recv(&self) -> Option<isize> {
+        self.state.borrow_mut().values.pop_front()
+    }
+
+    pub fn recv(&mut self) -> Next<'_, Channel> {
+        next(self)
+    }
+}
+
+impl Stream for Channel {
+    type Item = isize;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<isize>> {
+        let mut state = self.state.borrow_mut();
+
+        match state.values.pop_front() {
+            Some(value) => Poll::Ready(Some(value)),
+            None if state.is_closed => Poll::Ready(None),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// An IntCodeComputer whose input and output are channels
+pub struct AsyncIntCodeComputer {
+    computer: IntCodeComputer,
+    input: Channel,
+    output: Channel
+}
+
+impl AsyncIntCodeComputer {
+    pub fn new(computer: IntCodeComputer, input: Channel, output: Channel) -> AsyncIntCodeComputer {
+        AsyncIntCodeComputer { computer, input, output }
+    }
+
+    pub fn input(&self) -> Channel {
+        self.input.clone()
+    }
+
+    /// Output values as a Stream. The stream ends when the computer halts.
+    pub fn output(&self) -> Channel {
+        self.output.clone()
+    }
+
+    /// Runs the computer until it halts, or until it needs an input from a closed channel.
+    /// Returns the computer so that its final state can be inspected.
+    pub async fn run(mut self) -> IntCodeComputer {
+        loop {
+            self.computer.process(ReturnEvent::InputNeededEvent);
+
+            for value in self.computer.pop_all_output() {
+                self.output.send(value);
+            }
+
+            if self.computer.is_halted() {
+                break;
+            }
+
+            match self.input.recv().await {
+                Some(value) => self.computer.push_input(value),
+                None => break
+            }
+        }
+
+        self.output.close();
+        self.computer
+    }
+}
+
+type Task = Pin<Box<dyn Future<Output = ()>>>;
+
+struct TaskWaker {
+    id: usize,
+    ready: Arc<Mutex<VecDeque<usize>>>
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.ready.lock().unwrap().push_back(self.id);
+    }
+}
+
+/// Minimal single threaded executor: polls tasks whenever they are woken up
+#[derive(Default)]
+pub struct LocalExecutor {
+    tasks: Vec<Option<Task>>,
+    ready: Arc<Mutex<VecDeque<usize>>>
+}
+
+/// Result of a spawned task, available once the executor has run it to completion
+pub struct TaskResult<T> {
+    value: Rc<RefCell<Option<T>>>
+}
+
+impl<T> TaskResult<T> {
+    pub fn take(&self) -> Option<T> {
+        self.value.borrow_mut().take()
+    }
+}
+
+impl LocalExecutor {
+    pub fn new() -> LocalExecutor {
+        LocalExecutor::default()
+    }
+
+    pub fn spawn<T: 'static, F: Future<Output = T> + 'static>(&mut self, future: F) -> TaskResult<T> {
+        let value = Rc::new(RefCell::new(None));
+        let result = value.clone();
+
+        self.tasks.push(Some(Box::pin(async move {
+            *result.borrow_mut() = Some(future.await);
+        })));
+        self.ready.lock().unwrap().push_back(self.tasks.len() - 1);
+
+        TaskResult { value }
+    }
+
+    /// Runs until no task can make progress anymore.
+    /// Returns true if all the tasks have finished, false if some of them are stuck waiting on each other.
+    pub fn run(&mut self) -> bool {
+        loop {
+            let id = match self.ready.lock().unwrap().pop_front() {
+                Some(id) => id,
+                None => break
+            };
+
+            let waker = Waker::from(Arc::new(TaskWaker { id, ready: self.ready.clone() }));
+            let mut context = Context::from_waker(&waker);
+
+            if let Some(task) = self.tasks[id].as_mut() {
+                if task.as_mut().poll(&mut context).is_ready() {
+                    self.tasks[id] = None;
+                }
+            }
+        }
+
+        self.tasks.iter().all(|task| task.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_stream() {
+        let mut executor = LocalExecutor::new();
+        // Adds 1 to every input, forever
+        let computer = AsyncIntCodeComputer::new(IntCodeComputer::from("3,11,1001,11,1,11,4,11,1105,1,0"),
+                                                 Channel::new(), Channel::new());
+        let input = computer.input();
+        let mut output = computer.output();
+
+        executor.spawn(computer.run());
+        let sum = executor.spawn(async move {
+            let mut sum = 0;
+            while let Some(value) = next(&mut output).await {
+                sum += value;
+            }
+            sum
+        });
+
+        input.send(1);
+        input.send(2);
+        assert!(!executor.run());
+
+        input.send(3);
+        input.close();
+        assert!(executor.run());
+        assert_eq!(sum.take(), Some(2 + 3 + 4));
+    }
+}
@@ -1,5 +1,11 @@
+use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::convert::From;
+use std::fmt;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use super::intcode_recording::*;
 
 #[derive(Debug, Clone)]
 pub struct IntCodeComputer {
@@ -8,16 +14,135 @@ pub struct IntCodeComputer {
     relative_base: isize,
     memory: Vec<isize>,
     input: VecDeque<isize>,
-    output: VecDeque<isize>
+    output: VecDeque<isize>,
+    custom_opcodes: HashMap<isize, CustomOpcode>,
+    devices: Vec<MappedDevice>,
+    instruction_count: usize,
+    recording: Option<Recording>,
+    last_writes: Vec<(usize, isize)>,
+    fault: Option<String>
+}
+
+/// Everything that determines what the computer does next, given that no devices are attached.
+/// Trailing zeroes of the memory are left out, as the memory reads as 0 beyond its end anyway.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ComputerState {
+    pub is_halted: bool,
+    pub p: usize,
+    pub relative_base: isize,
+    pub memory: Vec<isize>,
+    pub input: Vec<isize>,
+    pub output: Vec<isize>
+}
+
+/// Something that can be attached to a range of addresses, so that the program's reads and writes
+/// of those addresses go to it instead of the memory. offset is relative to the start of the range.
+pub trait Device: Send {
+    fn read(&mut self, offset: usize) -> isize;
+    fn write(&mut self, offset: usize, value: isize);
+
+    /// How many addresses the device answers to, None when it takes any offset
+    fn size(&self) -> Option<usize> {
+        None
+    }
+}
+
+// Clones of the computer share the attached devices
+#[derive(Clone)]
+struct MappedDevice {
+    addresses: Range<usize>,
+    device: Arc<Mutex<dyn Device>>
+}
+
+impl fmt::Debug for MappedDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MappedDevice")
+         .field("addresses", &self.addresses)
+         .finish()
+    }
+}
+
+/// Gets the instruction's parameters (the address for the parameters it writes to, the value for the rest)
+/// and returns the values to be stored in the parameters it writes to, in order.
+/// An error halts the computer on the instruction, see fault().
+pub type OpcodeHandler = Arc<dyn Fn(&mut IntCodeComputer, &[isize]) -> Result<Vec<isize>, String> + Send + Sync>;
+
+/// An instruction registered on top of the default instruction set
+#[derive(Clone)]
+pub struct CustomOpcode {
+    arity: usize,
+    write_parameters: Vec<usize>,
+    handler: OpcodeHandler
+}
+
+impl fmt::Debug for CustomOpcode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CustomOpcode")
+         .field("arity", &self.arity)
+         .field("write_parameters", &self.write_parameters)
+         .finish()
+    }
 }
 
-#[derive(PartialEq)]
+const DEFAULT_OPCODES: [isize; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 99];
+
+/// The memory grows up to this many values, a program reading or writing further (outside of a device) faults
+pub const MEMORY_LIMIT: usize = 1 << 24;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ReturnEvent {
     InputNeededEvent,
     OutputReadyEvent,
     HaltEvent
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntCodeError {
+    /// The program asked for more input than it was given
+    InputExhausted,
+    /// The program counter went past the end of memory without halting
+    RanOffEnd { address: usize },
+    InvalidOpcode { address: usize, opcode: isize },
+    /// A custom opcode couldn't carry out the instruction at the address
+    Fault { address: usize, message: String }
+}
+
+impl fmt::Display for IntCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntCodeError::InputExhausted => write!(f, "The program asked for more input than it was given"),
+            IntCodeError::RanOffEnd { address } => write!(f, "The program ran off the end of memory at address {} without halting", address),
+            IntCodeError::InvalidOpcode { address, opcode } => write!(f, "Invalid opcode {} at address {}", opcode, address),
+            IntCodeError::Fault { address, message } => write!(f, "{} at address {}", message, address)
+        }
+    }
+}
+
+impl std::error::Error for IntCodeError {}
+
+/// Lazily runs the computer, yielding outputs as they are produced.
+/// Ends when the computer halts or needs an input that isn't available yet.
+pub struct Outputs<'a> {
+    computer: &'a mut IntCodeComputer
+}
+
+impl<'a> Iterator for Outputs<'a> {
+    type Item = isize;
+
+    fn next(&mut self) -> Option<isize> {
+        loop {
+            if self.computer.has_output() {
+                return Some(self.computer.pop_output());
+            }
+
+            match self.computer.step() {
+                Some(ReturnEvent::InputNeededEvent) | Some(ReturnEvent::HaltEvent) => return None,
+                _ => ()
+            }
+        }
+    }
+}
+
 impl IntCodeComputer {
     pub fn new() -> IntCodeComputer {
         IntCodeComputer {
@@ -26,11 +151,19 @@ impl IntCodeComputer {
             relative_base: 0,
             memory: Vec::new(),
             input: VecDeque::new(),
-            output: VecDeque::new()
+            output: VecDeque::new(),
+            custom_opcodes: HashMap::new(),
+            devices: Vec::new(),
+            instruction_count: 0,
+            recording: None,
+            last_writes: Vec::new(),
+            fault: None
         }
     }
 
+    /// Panics past MEMORY_LIMIT
     pub fn set_memory_value(&mut self, index: usize, value: isize) {
+        assert!(index < MEMORY_LIMIT, "Address {} is past the memory limit of {}", index, MEMORY_LIMIT);
         self.init_fetch(index);
         self.memory[index] = value;
     }
@@ -61,6 +194,178 @@ impl IntCodeComputer {
         self.is_halted
     }
 
+    /// Why a custom opcode halted the computer, if one did
+    pub fn fault(&self) -> Option<&str> {
+        self.fault.as_deref()
+    }
+
+    pub fn outputs(&mut self) -> Outputs<'_> {
+        Outputs { computer: self }
+    }
+
+    /// Groups the outputs in (x, y, value) like triples. An incomplete last triple is dropped.
+    pub fn outputs_in_triples(&mut self) -> impl Iterator<Item = (isize, isize, isize)> + '_ {
+        let mut outputs = self.outputs();
+        std::iter::from_fn(move || Some((outputs.next()?, outputs.next()?, outputs.next()?)))
+    }
+
+    /// Feeds all the inputs to the computer and runs it until it halts
+    /// Returns all the outputs produced, or an error if the program didn't halt cleanly
+    pub fn run_with_inputs<I: IntoIterator<Item = isize>>(&mut self, inputs: I) -> Result<Vec<isize>, IntCodeError> {
+        for value in inputs {
+            self.push_input(value);
+        }
+
+        self.process(ReturnEvent::InputNeededEvent);
+
+        if !self.is_halted && self.p >= self.memory.len() {
+            return Err(IntCodeError::RanOffEnd { address: self.p });
+        }
+        if !self.is_halted {
+            return Err(IntCodeError::InputExhausted);
+        }
+
+        // The program counter stays on the instruction that halted the computer
+        if let Some(message) = &self.fault {
+            return Err(IntCodeError::Fault { address: self.p, message: message.clone() });
+        }
+        let opcode = self.get_memory_value(self.p);
+        if opcode%100 != 99 {
+            return Err(IntCodeError::InvalidOpcode { address: self.p, opcode });
+        }
+
+        Ok(self.pop_all_output())
+    }
+
+    /// Reads a memory value without growing the memory. Unallocated memory reads as 0
+    pub fn get_memory_value(&self, index: usize) -> isize {
+        *self.memory.get(index).unwrap_or(&0)
+    }
+
+    pub fn memory(&self) -> &[isize] {
+        &self.memory
+    }
+
+    pub fn program_counter(&self) -> usize {
+        self.p
+    }
+
+    pub fn relative_base(&self) -> isize {
+        self.relative_base
+    }
+
+    /// Number of instructions executed so far
+    pub fn instruction_count(&self) -> usize {
+        self.instruction_count
+    }
+
+    /// (address, value) of the data written by the last executed instruction
+    pub fn last_writes(&self) -> &[(usize, isize)] {
+        &self.last_writes
+    }
+
+    /// Number of parameters taken by the instruction with the given opcode, None for unknown opcodes
+    pub fn opcode_arity(&self, opcode: isize) -> Option<usize> {
+        match opcode {
+            1 | 2 | 7 | 8 => Some(3),
+            5 | 6         => Some(2),
+            3 | 4 | 9     => Some(1),
+            99            => Some(0),
+            _             => self.custom_opcodes.get(&opcode).map(|custom_opcode| custom_opcode.arity)
+        }
+    }
+
+    /// Starts recording the inputs consumed and the outputs produced from now on, dropping any previous recording
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::default());
+    }
+
+    /// Stops recording and returns what was recorded
+    pub fn take_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    fn record(&mut self, event: RecordedEvent) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.events.push(event);
+        }
+    }
+
+    pub fn state(&self) -> ComputerState {
+        let memory_length = self.memory.iter().rposition(|&value| value != 0).map_or(0, |i| i + 1);
+
+        ComputerState {
+            is_halted: self.is_halted,
+            p: self.p,
+            relative_base: self.relative_base,
+            memory: self.memory[..memory_length].to_vec(),
+            input: self.input.iter().cloned().collect(),
+            output: self.output.iter().cloned().collect()
+        }
+    }
+
+    /// Adds an instruction to the computer's instruction set, to experiment with Intcode dialects.
+    /// write_parameters are the (1 based) positions of the parameters the instruction writes to.
+    /// The default opcodes can't be replaced, so that existing programs behave the same.
+    pub fn register_opcode<F>(&mut self, opcode: isize, arity: usize, write_parameters: &[usize], handler: F)
+        where F: Fn(&mut IntCodeComputer, &[isize]) -> Result<Vec<isize>, String> + Send + Sync + 'static {
+        assert!(opcode > 0 && opcode < 100, "Opcodes are two digits long");
+        assert!(!DEFAULT_OPCODES.contains(&opcode), "Opcode {} is part of the default instruction set", opcode);
+        assert!(write_parameters.iter().all(|&position| position >= 1 && position <= arity));
+
+        self.custom_opcodes.insert(opcode, CustomOpcode {
+            arity,
+            write_parameters: write_parameters.to_vec(),
+            handler: Arc::new(handler)
+        });
+    }
+
+    /// Maps the addresses start..start + length to the device. Returns the device,
+    /// so that the caller can still look at it while the program is using it.
+    /// Only the program's data reads and writes go to the device, instructions are always fetched from memory
+    /// and get_memory_value/set_memory_value keep working on the memory underneath.
+    /// Panics when the range is longer than the device or overlaps another device.
+    pub fn attach_device<D: Device + 'static>(&mut self, start: usize, length: usize, device: D) -> Arc<Mutex<D>> {
+        if let Some(size) = device.size() {
+            assert!(length <= size, "The device has {} addresses, can not map {} to it", size, length);
+        }
+        let end = start.checked_add(length).expect("The device's addresses go past the end of memory");
+        let addresses = start..end;
+        assert!(self.devices.iter().all(|d| d.addresses.end <= addresses.start || addresses.end <= d.addresses.start),
+                "Devices can not overlap");
+
+        let device = Arc::new(Mutex::new(device));
+        self.devices.push(MappedDevice { addresses, device: device.clone() });
+        device
+    }
+
+    /// Reads the address the way the program's instructions do, from the device mapped there if any
+    pub fn read_memory(&mut self, address: usize) -> isize {
+        match self.devices.iter().find(|d| d.addresses.contains(&address)) {
+            Some(d) => d.device.lock().unwrap().read(address - d.addresses.start),
+            None => self.get_memory_value(address)
+        }
+    }
+
+    /// Writes the address the way the program's instructions do, to the device mapped there if any,
+    /// and keeps it in last_writes. Panics past MEMORY_LIMIT when no device is mapped there
+    pub fn write_memory(&mut self, address: usize, value: isize) {
+        assert!(address < MEMORY_LIMIT || self.is_mapped(address), "Address {} is past the memory limit of {}", address, MEMORY_LIMIT);
+        self.last_writes.push((address, value));
+
+        match self.devices.iter().find(|d| d.addresses.contains(&address)) {
+            Some(d) => d.device.lock().unwrap().write(address - d.addresses.start, value),
+            None => {
+                self.init_fetch(address); //Make sure it exists
+                self.memory[address] = value;
+            }
+        }
+    }
+
+    fn is_mapped(&self, address: usize) -> bool {
+        self.devices.iter().any(|d| d.addresses.contains(&address))
+    }
+
     fn init_fetch(&mut self, memory_location: usize) -> isize {
         if memory_location >= self.memory.len() {
             self.memory.resize(memory_location + 1, 0);
@@ -69,10 +374,16 @@ impl IntCodeComputer {
         self.memory[memory_location]
     }
 
-    fn current_parameter_mode(&mut self, parameter_position: usize) -> isize {
+    fn current_parameter_mode(&self, parameter_position: usize) -> isize {
         // Rightmost 2 digits = opcode
         // After that each digit represents parameter mode
-        (self.init_fetch(self.p)/100/((10 as isize).pow(parameter_position as u32 - 1)))%10
+        (self.get_memory_value(self.p)/100/((10 as isize).pow(parameter_position as u32 - 1)))%10
+    }
+
+    // Parameters that an instruction writes to will never be in immediate mode.
+    fn current_parameter_address(&mut self, parameter_position: usize) -> isize {
+        let base_value = self.init_fetch(self.p + parameter_position);
+        if self.current_parameter_mode(parameter_position) == 2 { self.relative_base + base_value } else { base_value }
     }
 
     fn current_parameter_value(&mut self, parameter_position: usize) -> isize {
@@ -81,95 +392,199 @@ impl IntCodeComputer {
         // 1 => Immediate mode
         // 0 => Position mode
         match self.current_parameter_mode(parameter_position) {
-            2 => { self.init_fetch((self.relative_base + base_value) as usize) },
+            2 => { self.read_memory((self.relative_base + base_value) as usize) },
             1 => { base_value },
-            _ => { self.init_fetch(base_value as usize) }
+            _ => { self.read_memory(base_value as usize) }
         }
     }
 
-    pub fn process(&mut self, return_event: ReturnEvent) -> bool {
+    // Checks the addresses the instruction's parameters refer to before anything is executed,
+    // so that a bad one halts the computer on the instruction instead of panicking or growing the memory without bound
+    fn check_parameter_addresses(&self, opcode: isize) -> Result<(), String> {
+        let write_parameters = match opcode {
+            1 | 2 | 7 | 8 => vec![3],
+            3             => vec![1],
+            _             => self.custom_opcodes.get(&opcode).map_or(Vec::new(), |custom_opcode| custom_opcode.write_parameters.clone())
+        };
+
+        for position in 1..=self.opcode_arity(opcode).unwrap_or(0) {
+            let base_value = self.get_memory_value(self.p + position);
+            let address = match self.current_parameter_mode(position) {
+                // Parameters written to are never in immediate mode
+                1 if !write_parameters.contains(&position) => continue,
+                2 => self.relative_base.checked_add(base_value)
+                                       .ok_or_else(|| format!("Parameter {} refers to an address that doesn't fit in 64 bits", position))?,
+                _ => base_value
+            };
+
+            if address < 0 || (address as usize >= MEMORY_LIMIT && !self.is_mapped(address as usize)) {
+                return Err(format!("Parameter {} refers to address {}, outside of the memory", position, address));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Halts the computer on the instruction, see fault()
+    fn halt_with_fault(&mut self, message: String) -> Option<ReturnEvent> {
+        self.fault = Some(message);
+        self.is_halted = true;
+        Some(ReturnEvent::HaltEvent)
+    }
+
+    /// Executes a single instruction and returns the event it caused, if any:
+    /// InputNeededEvent when the instruction needs an input that isn't available yet (nothing is executed then),
+    /// OutputReadyEvent when the instruction produced an output and HaltEvent when the computer halted
+    pub fn step(&mut self) -> Option<ReturnEvent> {
         if self.is_halted {
-            return self.is_halted;
+            return Some(ReturnEvent::HaltEvent);
         }
 
-        while self.p < self.memory.len() {
-            let opcode = self.memory[self.p]%100;
-            let p = self.p;
-
-            // Parameters that an instruction writes to will never be in immediate mode.
-            let write_location = match opcode {
-                1 | 2 | 7 | 8 => if self.current_parameter_mode(3) == 2 { self.relative_base + self.init_fetch(p+3) } else { self.init_fetch(p+3) },
-                3             => if self.current_parameter_mode(1) == 2 { self.relative_base + self.init_fetch(p+1) } else { self.init_fetch(p+1) },
-                _             => 0
-            } as usize;
-
-            self.init_fetch(write_location); //Make sure it exists
-
-            match opcode {
-                1 => {
-                    self.memory[write_location] = self.current_parameter_value(1) + self.current_parameter_value(2);
-                    self.p += 4;
-                },
-                2 => {
-                    self.memory[write_location] = self.current_parameter_value(1) * self.current_parameter_value(2);
-                    self.p +=4;
-                },
-                3 => {
-                    if return_event == ReturnEvent::InputNeededEvent && self.input.is_empty() {
-                        break;
-                    }
+        let opcode = self.get_memory_value(self.p)%100;
+        let p = self.p;
+
+        // Nothing is executed until someone pushes an input
+        if opcode == 3 && self.input.is_empty() {
+            return Some(ReturnEvent::InputNeededEvent);
+        }
 
-                    self.memory[write_location] = self.input.pop_front().unwrap();
-                    self.p += 2;
-                },
-                4 => {
-                    let output_parameter = self.current_parameter_value(1);
-                    self.output.push_back(output_parameter);
-                    self.p += 2;
+        let at = self.instruction_count;
+        self.instruction_count += 1;
+        self.last_writes.clear();
 
-                    if return_event == ReturnEvent::OutputReadyEvent {
-                        break;
-                    }
-                },
-                5 => {
-                    if self.current_parameter_value(1) != 0 {
-                        self.p = self.current_parameter_value(2) as usize;
-                    }
-                    else {
-                        self.p += 3;
-                    }
-                },
-                6 => {
-                    if self.current_parameter_value(1) == 0 {
-                        self.p = self.current_parameter_value(2) as usize;
-                    }
-                    else {
-                        self.p += 3;
+        if let Err(message) = self.check_parameter_addresses(opcode) {
+            return self.halt_with_fault(message);
+        }
+
+        let write_location = match opcode {
+            1 | 2 | 7 | 8 => self.current_parameter_address(3),
+            3             => self.current_parameter_address(1),
+            _             => 0
+        } as usize;
+
+        match opcode {
+            1 => {
+                let value = match self.current_parameter_value(1).checked_add(self.current_parameter_value(2)) {
+                    Some(value) => value,
+                    None => return self.halt_with_fault(String::from("The addition overflows"))
+                };
+                self.write_memory(write_location, value);
+                self.p += 4;
+            },
+            2 => {
+                let value = match self.current_parameter_value(1).checked_mul(self.current_parameter_value(2)) {
+                    Some(value) => value,
+                    None => return self.halt_with_fault(String::from("The multiplication overflows"))
+                };
+                self.write_memory(write_location, value);
+                self.p +=4;
+            },
+            3 => {
+                let value = self.input.pop_front().unwrap();
+                self.record(RecordedEvent::Input { at, value });
+                self.write_memory(write_location, value);
+                self.p += 2;
+            },
+            4 => {
+                let output_parameter = self.current_parameter_value(1);
+                self.output.push_back(output_parameter);
+                self.record(RecordedEvent::Output { at, value: output_parameter });
+                self.p += 2;
+
+                return Some(ReturnEvent::OutputReadyEvent);
+            },
+            5 => {
+                if self.current_parameter_value(1) != 0 {
+                    let target = self.current_parameter_value(2);
+                    if target < 0 {
+                        return self.halt_with_fault(format!("Jumps to address {}, outside of the memory", target));
                     }
-                },
-                7 => {
-                    self.memory[write_location] = (self.current_parameter_value(1) < self.current_parameter_value(2)) as isize;
-                    self.p += 4;
-                },
-                8 => {
-                    self.memory[write_location] = (self.current_parameter_value(1) == self.current_parameter_value(2)) as isize;
-                    self.p += 4;
-                },
-                9 => {
-                    self.relative_base = self.relative_base + self.current_parameter_value(1);
-                    self.p += 2;
+                    self.p = target as usize;
                 }
-                _ => {
-                    if opcode != 99 {
-                        println!("Invalid operation!: {} ", opcode);
+                else {
+                    self.p += 3;
+                }
+            },
+            6 => {
+                if self.current_parameter_value(1) == 0 {
+                    let target = self.current_parameter_value(2);
+                    if target < 0 {
+                        return self.halt_with_fault(format!("Jumps to address {}, outside of the memory", target));
                     }
-                    self.is_halted = true;
-                    break
+                    self.p = target as usize;
                 }
-            };
+                else {
+                    self.p += 3;
+                }
+            },
+            7 => {
+                let value = (self.current_parameter_value(1) < self.current_parameter_value(2)) as isize;
+                self.write_memory(write_location, value);
+                self.p += 4;
+            },
+            8 => {
+                let value = (self.current_parameter_value(1) == self.current_parameter_value(2)) as isize;
+                self.write_memory(write_location, value);
+                self.p += 4;
+            },
+            9 => {
+                self.relative_base = match self.relative_base.checked_add(self.current_parameter_value(1)) {
+                    Some(relative_base) => relative_base,
+                    None => return self.halt_with_fault(String::from("The relative base overflows"))
+                };
+                self.p += 2;
+            }
+            _ if self.custom_opcodes.contains_key(&opcode) => {
+                let custom_opcode = self.custom_opcodes[&opcode].clone();
+                let parameters = (1..=custom_opcode.arity).map(|i| if custom_opcode.write_parameters.contains(&i) { self.current_parameter_address(i) }
+                                                                   else { self.current_parameter_value(i) })
+                                                          .collect::<Vec<isize>>();
+
+                let results = match (custom_opcode.handler)(self, &parameters) {
+                    Ok(results) => results,
+                    // Stays on the instruction, like the other instructions that halt
+                    Err(message) => return self.halt_with_fault(message)
+                };
+
+                for (&position, value) in custom_opcode.write_parameters.iter().zip(results) {
+                    self.write_memory(parameters[position - 1] as usize, value);
+                }
+
+                self.p = p + custom_opcode.arity + 1;
+            },
+            _ => {
+                if opcode != 99 {
+                    println!("Invalid operation!: {} ", opcode);
+                }
+                self.is_halted = true;
+                return Some(ReturnEvent::HaltEvent);
+            }
+        };
+
+        None
+    }
+
+    /// Runs the program until it halts, or until the first output when return_event is OutputReadyEvent.
+    /// Whatever return_event is, it also stops before an input instruction when no input is available,
+    /// without executing it, so that it carries on from there once an input is pushed.
+    /// Returns whether the computer halted.
+    pub fn process(&mut self, return_event: ReturnEvent) -> bool {
+        while !self.is_halted && self.p < self.memory.len() {
+            match self.step() {
+                // Nothing more can be done until someone pushes an input
+                Some(ReturnEvent::InputNeededEvent) => break,
+                Some(ReturnEvent::OutputReadyEvent) if return_event == ReturnEvent::OutputReadyEvent => break,
+                _ => ()
+            }
         }
 
-        return self.is_halted;
+        self.is_halted
+    }
+}
+
+impl Default for IntCodeComputer {
+    fn default() -> Self {
+        IntCodeComputer::new()
     }
 }
 
@@ -187,7 +602,13 @@ impl From<&str> for IntCodeComputer {
                     .collect::<Vec<isize>>()
             },
             input: VecDeque::new(),
-            output: VecDeque::new()
+            output: VecDeque::new(),
+            custom_opcodes: HashMap::new(),
+            devices: Vec::new(),
+            instruction_count: 0,
+            recording: None,
+            last_writes: Vec::new(),
+            fault: None
         }
     }
 }
@@ -200,7 +621,13 @@ impl From<Vec<isize>> for IntCodeComputer {
             relative_base: 0,
             memory: instructions,
             input: VecDeque::new(),
-            output: VecDeque::new()
+            output: VecDeque::new(),
+            custom_opcodes: HashMap::new(),
+            devices: Vec::new(),
+            instruction_count: 0,
+            recording: None,
+            last_writes: Vec::new(),
+            fault: None
         }
     }
 }
@@ -298,4 +725,94 @@ mod tests {
         assert_eq!(fetch_output_for(program.clone(), &mut vec![8]), [1000]);
         assert_eq!(fetch_output_for(program.clone(), &mut vec![9]), [1001]);
     }
+
+    #[test]
+    fn test_run_with_inputs() {
+        // Outputs the sum of two inputs
+        let program = vec![3,11,3,12,1,11,12,13,4,13,99];
+        assert_eq!(IntCodeComputer::from(program.clone()).run_with_inputs(vec![3, 4]), Ok(vec![7]));
+        assert_eq!(IntCodeComputer::from(program.clone()).run_with_inputs(vec![3]), Err(IntCodeError::InputExhausted));
+        assert_eq!(IntCodeComputer::from(vec![104,5,42]).run_with_inputs(vec![]),
+                   Err(IntCodeError::InvalidOpcode { address: 2, opcode: 42 }));
+        assert_eq!(IntCodeComputer::from(vec![104,5]).run_with_inputs(vec![]), Err(IntCodeError::RanOffEnd { address: 2 }));
+    }
+
+    #[test]
+    fn test_outputs() {
+        // Echoes its inputs forever
+        let mut computer = IntCodeComputer::from(vec![3,7,4,7,1105,1,0]);
+        computer.push_input(1);
+        computer.push_input(2);
+        assert_eq!(computer.outputs().collect::<Vec<isize>>(), [1, 2]);
+
+        for value in 3..9 {
+            computer.push_input(value);
+        }
+        assert_eq!(computer.outputs_in_triples().collect::<Vec<(isize, isize, isize)>>(), [(3, 4, 5), (6, 7, 8)]);
+    }
+
+    #[test]
+    fn test_custom_opcodes() {
+        use std::sync::Mutex;
+
+        let printed = Arc::new(Mutex::new(Vec::new()));
+        let log = printed.clone();
+
+        // 20,a,b,c => c = max(a, b) and 50,a => debug print a
+        let mut computer = IntCodeComputer::from(vec![20,9,10,11,150,-7,50,11,99,3,12,0]);
+        computer.register_opcode(20, 3, &[3], |_, parameters| Ok(vec![std::cmp::max(parameters[0], parameters[1])]));
+        computer.register_opcode(50, 1, &[], move |_, parameters| {
+            log.lock().unwrap().push(parameters[0]);
+            Ok(Vec::new())
+        });
+
+        computer.process(ReturnEvent::HaltEvent);
+        assert_eq!(computer.get_memory_value(11), 12);
+        assert_eq!(*printed.lock().unwrap(), [-7, 12]);
+
+        // The default instruction set stays the same for programs that don't use the custom opcodes
+        computer = IntCodeComputer::from(vec![1,0,0,0,4,0,99]);
+        computer.register_opcode(20, 3, &[3], |_, _| Ok(vec![0]));
+        computer.process(ReturnEvent::HaltEvent);
+        assert_eq!(computer.pop_all_output(), [2]);
+
+        // A failing instruction halts the program on it
+        computer = IntCodeComputer::from(vec![104,1,50,0,104,2,99]);
+        computer.register_opcode(50, 1, &[], |_, _| Err(String::from("Out of paper")));
+        assert_eq!(computer.run_with_inputs(vec![]), Err(IntCodeError::Fault { address: 2, message: String::from("Out of paper") }));
+        assert_eq!(computer.fault(), Some("Out of paper"));
+        assert_eq!(computer.pop_all_output(), [1]);
+    }
+
+    #[test]
+    fn test_process_waits_for_input() {
+        // Outputs the sum of two inputs
+        let mut computer = IntCodeComputer::from(vec![3,11,3,12,1,11,12,13,4,13,99]);
+        computer.push_input(3);
+
+        assert!(!computer.process(ReturnEvent::HaltEvent));
+        assert_eq!((computer.program_counter(), computer.instruction_count()), (2, 1));
+        assert!(!computer.has_output());
+
+        computer.push_input(4);
+        assert!(computer.process(ReturnEvent::HaltEvent));
+        assert_eq!(computer.pop_all_output(), [7]);
+    }
+
+    #[test]
+    fn test_faults() {
+        let fault = |program: Vec<isize>| IntCodeComputer::from(program).run_with_inputs(vec![]);
+
+        assert_eq!(fault(vec![1101,1,1,-1,99]), Err(IntCodeError::Fault { address: 0, message: String::from("Parameter 3 refers to address -1, outside of the memory") }));
+        assert_eq!(fault(vec![4,1000000000000,99]), Err(IntCodeError::Fault { address: 0, message: String::from("Parameter 1 refers to address 1000000000000, outside of the memory") }));
+        assert_eq!(fault(vec![109,-5,204,2,99]), Err(IntCodeError::Fault { address: 2, message: String::from("Parameter 1 refers to address -3, outside of the memory") }));
+        assert_eq!(fault(vec![1102,isize::MAX,2,0,99]), Err(IntCodeError::Fault { address: 0, message: String::from("The multiplication overflows") }));
+        assert_eq!(fault(vec![1105,1,-4]), Err(IntCodeError::Fault { address: 0, message: String::from("Jumps to address -4, outside of the memory") }));
+
+        // Nothing is written by the faulting instruction and the memory stays as it was
+        let mut computer = IntCodeComputer::from(vec![1101,1,1,16777216,99]);
+        assert!(computer.process(ReturnEvent::HaltEvent));
+        assert_eq!(computer.memory().len(), 5);
+        assert_eq!(computer.last_writes(), []);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,78 @@
+// Tells "slow" programs apart from programs that are provably stuck in an infinite loop:
+// If the computer ever comes back to a state it has already been in, it will loop forever.
+// Keeping every state around would be too expensive, so the state is only recorded every `interval`
+// instructions. A loop of any length still gets caught, it just takes a few more rounds of it.
+//
+// Attached devices are not part of the state, so programs using them can't be checked this way.
+
+use std::collections::HashMap;
+
+use super::intcode_computer::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunOutcome {
+    Halted,
+    InputNeeded,
+    /// The state seen after first_seen instructions came back after repeated_at instructions
+    Stuck { first_seen: usize, repeated_at: usize },
+    /// Ran out of the instruction budget without being able to tell
+    BudgetExhausted
+}
+
+/// Runs the computer until it halts, needs input, or is found to be looping forever.
+/// max_instructions limits how many more instructions are executed, if given.
+pub fn run_with_cycle_detection(computer: &mut IntCodeComputer, interval: usize, max_instructions: Option<usize>) -> RunOutcome {
+    assert!(interval > 0);
+
+    let start = computer.instruction_count();
+    let mut seen_states: HashMap<ComputerState, usize> = HashMap::new();
+
+    loop {
+        let executed = computer.instruction_count() - start;
+
+        if executed % interval == 0 {
+            let state = computer.state();
+
+            if let Some(&first_seen) = seen_states.get(&state) {
+                return RunOutcome::Stuck { first_seen, repeated_at: computer.instruction_count() };
+            }
+
+            seen_states.insert(state, computer.instruction_count());
+        }
+
+        if max_instructions.is_some_and(|max| executed >= max) {
+            return RunOutcome::BudgetExhausted;
+        }
+
+        match computer.step() {
+            Some(ReturnEvent::HaltEvent) => return RunOutcome::Halted,
+            Some(ReturnEvent::InputNeededEvent) => return RunOutcome::InputNeeded,
+            _ => ()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_detection() {
+        // Jumps back to itself forever
+        let mut computer = IntCodeComputer::from("1105,1,0");
+        assert_eq!(run_with_cycle_detection(&mut computer, 1, None), RunOutcome::Stuck { first_seen: 0, repeated_at: 1 });
+
+        // Increments a counter forever: never repeats, but never finishes either
+        let mut computer = IntCodeComputer::from("1001,7,1,7,1105,1,0");
+        assert_eq!(run_with_cycle_detection(&mut computer, 3, Some(1000)), RunOutcome::BudgetExhausted);
+
+        // Toggles a cell between 0 and 1 forever, so the state only repeats every 4 instructions
+        let mut computer = IntCodeComputer::from("1008,7,0,7,1105,1,0,0");
+        assert_eq!(run_with_cycle_detection(&mut computer, 3, Some(1000)), RunOutcome::Stuck { first_seen: 0, repeated_at: 12 });
+
+        let mut computer = IntCodeComputer::from("3,0,99");
+        assert_eq!(run_with_cycle_detection(&mut computer, 10, None), RunOutcome::InputNeeded);
+        computer.push_input(1);
+        assert_eq!(run_with_cycle_detection(&mut computer, 10, None), RunOutcome::Halted);
+    }
+}
@@ -0,0 +1,368 @@
+// Exposes a long running IntCodeComputer over a local socket, so that editor plugins and
+// visualizers written in other languages can drive it without linking against this crate.
+//
+// The protocol is line based: every request is a single line JSON object with a "command" field
+// and every response is a single line JSON object with an "ok" field. Examples:
+//   {"command": "load", "program": "1,0,0,0,99"}
+//   {"command": "break", "address": 4}          (and "clear" to remove it again)
+//   {"command": "step", "count": 10}
+//   {"command": "continue"}                      (stops with the event "budget" after a million instructions)
+//   {"command": "read", "address": 0, "length": 8}
+//   {"command": "write", "address": 0, "value": 2}
+//   {"command": "push", "values": [1, 2, 3]}
+//   {"command": "pop"}
+//   {"command": "state"}
+//
+// A client can't make the server allocate without bound: request lines are capped at REQUEST_LIMIT bytes,
+// reads at READ_LIMIT values and neither writes nor the program can grow the memory past MEMORY_LIMIT.
+// A request that panics gets an error response, the session and the server carry on.
+
+use std::collections::BTreeSet;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::panic::{self, AssertUnwindSafe};
+
+use super::intcode_computer::*;
+use super::json::*;
+
+/// Most instructions a single step or continue request runs, so that a looping program can't hang the server
+pub const INSTRUCTION_BUDGET: usize = 1_000_000;
+pub const READ_LIMIT: usize = 4096;
+/// Longest request line in bytes, newline included
+pub const REQUEST_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Clone, Default)]
+pub struct DebugSession {
+    computer: IntCodeComputer,
+    breakpoints: BTreeSet<usize>
+}
+
+fn event_name(event: Option<ReturnEvent>) -> JsonValue {
+    match event {
+        Some(ReturnEvent::InputNeededEvent) => JsonValue::from("input_needed"),
+        Some(ReturnEvent::OutputReadyEvent) => JsonValue::from("output_ready"),
+        Some(ReturnEvent::HaltEvent) => JsonValue::from("halt"),
+        None => JsonValue::Null
+    }
+}
+
+fn error(message: &str) -> JsonValue {
+    JsonValue::object(vec![("ok", JsonValue::from(false)),
+                           ("error", JsonValue::from(message))])
+}
+
+fn integer_field(request: &JsonValue, key: &str) -> Result<i64, String> {
+    request.get(key)
+           .and_then(|value| value.as_i64())
+           .ok_or_else(|| format!("Expected an integer \"{}\"", key))
+}
+
+fn address_field(request: &JsonValue, key: &str) -> Result<usize, String> {
+    let value = integer_field(request, key)?;
+    if value < 0 {
+        return Err(format!("\"{}\" can not be negative", key));
+    }
+    Ok(value as usize)
+}
+
+impl DebugSession {
+    pub fn new() -> DebugSession {
+        DebugSession::default()
+    }
+
+    pub fn with_computer(computer: IntCodeComputer) -> DebugSession {
+        DebugSession {
+            computer,
+            breakpoints: BTreeSet::new()
+        }
+    }
+
+    pub fn computer(&self) -> &IntCodeComputer {
+        &self.computer
+    }
+
+    /// Handles a single request line and returns the response line
+    pub fn handle(&mut self, line: &str) -> String {
+        let response = match parse(line) {
+            Ok(request) => panic::catch_unwind(AssertUnwindSafe(|| self.handle_request(&request)))
+                                .unwrap_or_else(|_| Err(String::from("The request panicked")))
+                                .unwrap_or_else(|message| error(&message)),
+            Err(message) => error(&format!("Invalid JSON: {}", message))
+        };
+
+        response.to_string()
+    }
+
+    fn state(&self, event: JsonValue) -> JsonValue {
+        JsonValue::object(vec![("ok", JsonValue::from(true)),
+                               ("p", JsonValue::from(self.computer.program_counter())),
+                               ("relative_base", JsonValue::from(self.computer.relative_base())),
+                               ("halted", JsonValue::from(self.computer.is_halted())),
+                               ("event", event)])
+    }
+
+    fn handle_request(&mut self, request: &JsonValue) -> Result<JsonValue, String> {
+        let command = request.get("command")
+                             .and_then(|value| value.as_str())
+                             .ok_or_else(|| String::from("Expected a \"command\""))?;
+
+        match command {
+            "load" => {
+                let program = request.get("program")
+                                     .and_then(|value| value.as_str())
+                                     .ok_or_else(|| String::from("Expected a \"program\""))?;
+
+                if program.split(',').any(|item| !item.trim().is_empty() && item.trim().parse::<isize>().is_err()) {
+                    return Err(String::from("The program must be comma separated integers"));
+                }
+
+                self.computer = IntCodeComputer::from(program);
+                Ok(self.state(JsonValue::Null))
+            },
+            "step" => {
+                let count = request.get("count").and_then(|value| value.as_i64()).unwrap_or(1);
+                let mut event = None;
+
+                for _ in 0..count.clamp(0, INSTRUCTION_BUDGET as i64) {
+                    event = self.computer.step();
+                    if event == Some(ReturnEvent::InputNeededEvent) || event == Some(ReturnEvent::HaltEvent) {
+                        break;
+                    }
+                }
+
+                Ok(self.state(event_name(event)))
+            },
+            "continue" => {
+                // Always execute at least one instruction, so that continuing from a breakpoint moves on
+                for _ in 0..INSTRUCTION_BUDGET {
+                    let event = self.computer.step();
+
+                    if event == Some(ReturnEvent::InputNeededEvent) || event == Some(ReturnEvent::HaltEvent) {
+                        return Ok(self.state(event_name(event)));
+                    }
+
+                    if self.breakpoints.contains(&self.computer.program_counter()) {
+                        return Ok(self.state(JsonValue::from("breakpoint")));
+                    }
+                }
+
+                Ok(self.state(JsonValue::from("budget")))
+            },
+            "break" => {
+                self.breakpoints.insert(address_field(request, "address")?);
+                Ok(self.breakpoint_list())
+            },
+            "clear" => {
+                self.breakpoints.remove(&address_field(request, "address")?);
+                Ok(self.breakpoint_list())
+            },
+            "breakpoints" => Ok(self.breakpoint_list()),
+            "read" => {
+                let address = address_field(request, "address")?;
+                let length = request.get("length").and_then(|value| value.as_i64()).unwrap_or(1).max(0) as usize;
+                if length > READ_LIMIT {
+                    return Err(format!("Can not read more than {} values at once", READ_LIMIT));
+                }
+                let end = address.checked_add(length).ok_or_else(|| String::from("\"address\" is too large"))?;
+                let values = (address..end).map(|i| self.computer.get_memory_value(i))
+                                           .collect::<Vec<isize>>();
+
+                Ok(JsonValue::object(vec![("ok", JsonValue::from(true)),
+                                          ("address", JsonValue::from(address)),
+                                          ("values", JsonValue::from(values))]))
+            },
+            "write" => {
+                let address = address_field(request, "address")?;
+                let value = integer_field(request, "value")?;
+                if address >= MEMORY_LIMIT {
+                    return Err(format!("Can not write past the memory limit of {}", MEMORY_LIMIT));
+                }
+                self.computer.set_memory_value(address, value as isize);
+                Ok(self.state(JsonValue::Null))
+            },
+            "push" => {
+                let values = match (request.get("value"), request.get("values")) {
+                    (Some(value), _) => vec![value.clone()],
+                    (None, Some(JsonValue::Array(values))) => values.clone(),
+                    _ => return Err(String::from("Expected a \"value\" or \"values\""))
+                };
+
+                for value in values.iter() {
+                    let value = value.as_i64().ok_or_else(|| String::from("Only integers can be pushed"))?;
+                    self.computer.push_input(value as isize);
+                }
+
+                Ok(self.state(JsonValue::Null))
+            },
+            "pop" => {
+                Ok(JsonValue::object(vec![("ok", JsonValue::from(true)),
+                                          ("values", JsonValue::from(self.computer.pop_all_output()))]))
+            },
+            "state" => Ok(self.state(JsonValue::Null)),
+            _ => Err(format!("Unknown command: {}", command))
+        }
+    }
+
+    fn breakpoint_list(&self) -> JsonValue {
+        JsonValue::object(vec![("ok", JsonValue::from(true)),
+                               ("breakpoints", JsonValue::Array(self.breakpoints.iter()
+                                                                                .map(|&b| JsonValue::from(b))
+                                                                                .collect()))])
+    }
+
+    /// Serves requests from a single client until it disconnects
+    pub fn serve_client<S: std::io::Read + Write>(&mut self, stream: S) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+
+        while reader.by_ref().take(REQUEST_LIMIT as u64).read_line(&mut line)? > 0 {
+            // Whatever is left of a line that is too long can't be told apart from the next request
+            if line.len() == REQUEST_LIMIT && !line.ends_with('\n') {
+                let stream = reader.get_mut();
+                stream.write_all(error(&format!("Requests can not be longer than {} bytes", REQUEST_LIMIT)).to_string().as_bytes())?;
+                stream.write_all(b"\n")?;
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Request too long"));
+            }
+
+            if !line.trim().is_empty() {
+                let response = self.handle(line.trim());
+                let stream = reader.get_mut();
+                stream.write_all(response.as_bytes())?;
+                stream.write_all(b"\n")?;
+                stream.flush()?;
+            }
+            line.clear();
+        }
+
+        Ok(())
+    }
+}
+
+/// Serves the session over TCP on localhost, one client at a time.
+/// The session outlives the connections, so clients can detach and attach again.
+/// A client whose connection fails is let go of, the server keeps waiting for the next one.
+pub fn serve_tcp(session: &mut DebugSession, port: u16) -> std::io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+
+    for stream in listener.incoming() {
+        if let Err(error) = stream.and_then(|stream| session.serve_client(stream)) {
+            println!("Debug client dropped: {}", error);
+        }
+    }
+
+    Ok(())
+}
+
+/// Same as serve_tcp, but over a unix domain socket at the given path
+#[cfg(unix)]
+pub fn serve_unix(session: &mut DebugSession, path: &str) -> std::io::Result<()> {
+    let listener = std::os::unix::net::UnixListener::bind(path)?;
+
+    for stream in listener.incoming() {
+        if let Err(error) = stream.and_then(|stream| session.serve_client(stream)) {
+            println!("Debug client dropped: {}", error);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session() {
+        let mut session = DebugSession::new();
+
+        // Reads an input, adds 100 to it and outputs it
+        assert_eq!(session.handle(r#"{"command": "load", "program": "3,9,1001,9,100,9,4,9,99,0"}"#),
+                   r#"{"ok":true,"p":0,"relative_base":0,"halted":false,"event":null}"#);
+        assert_eq!(session.handle(r#"{"command": "break", "address": 6}"#),
+                   r#"{"ok":true,"breakpoints":[6]}"#);
+        assert_eq!(session.handle(r#"{"command": "continue"}"#),
+                   r#"{"ok":true,"p":0,"relative_base":0,"halted":false,"event":"input_needed"}"#);
+        assert_eq!(session.handle(r#"{"command": "push", "values": [23]}"#),
+                   r#"{"ok":true,"p":0,"relative_base":0,"halted":false,"event":null}"#);
+        assert_eq!(session.handle(r#"{"command": "continue"}"#),
+                   r#"{"ok":true,"p":6,"relative_base":0,"halted":false,"event":"breakpoint"}"#);
+        assert_eq!(session.handle(r#"{"command": "read", "address": 9}"#),
+                   r#"{"ok":true,"address":9,"values":[123]}"#);
+        assert_eq!(session.handle(r#"{"command": "step"}"#),
+                   r#"{"ok":true,"p":8,"relative_base":0,"halted":false,"event":"output_ready"}"#);
+        assert_eq!(session.handle(r#"{"command": "continue"}"#),
+                   r#"{"ok":true,"p":8,"relative_base":0,"halted":true,"event":"halt"}"#);
+        assert_eq!(session.handle(r#"{"command": "pop"}"#),
+                   r#"{"ok":true,"values":[123]}"#);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut session = DebugSession::new();
+
+        assert_eq!(session.handle("hello"), r#"{"ok":false,"error":"Invalid JSON: Unexpected character at 0"}"#);
+        assert_eq!(session.handle(r#"{"command": "fly"}"#), r#"{"ok":false,"error":"Unknown command: fly"}"#);
+        assert_eq!(session.handle(r#"{"command": "read", "address": -1}"#),
+                   r#"{"ok":false,"error":"\"address\" can not be negative"}"#);
+        assert_eq!(session.handle(r#"{"command": "read", "address": 0, "length": 100000000}"#),
+                   r#"{"ok":false,"error":"Can not read more than 4096 values at once"}"#);
+        assert_eq!(session.handle(r#"{"command": "write", "address": 1000000000000, "value": 1}"#),
+                   r#"{"ok":false,"error":"Can not write past the memory limit of 16777216"}"#);
+
+        // Writing out of memory or far into it faults instead of panicking or allocating
+        session.handle(r#"{"command": "load", "program": "1101,1,1,-1,99"}"#);
+        assert_eq!(session.handle(r#"{"command": "step"}"#),
+                   r#"{"ok":true,"p":0,"relative_base":0,"halted":true,"event":"halt"}"#);
+        session.handle(r#"{"command": "load", "program": "1101,1,1,1000000000000,99"}"#);
+        assert_eq!(session.handle(r#"{"command": "continue"}"#),
+                   r#"{"ok":true,"p":0,"relative_base":0,"halted":true,"event":"halt"}"#);
+        assert_eq!(session.computer().fault(), Some("Parameter 3 refers to address 1000000000000, outside of the memory"));
+
+        // A panicking custom opcode doesn't take the session down
+        let mut computer = IntCodeComputer::from("50,0,99");
+        computer.register_opcode(50, 1, &[], |_, _| panic!("Broken opcode"));
+        session = DebugSession::with_computer(computer);
+        assert_eq!(session.handle(r#"{"command": "step"}"#), r#"{"ok":false,"error":"The request panicked"}"#);
+        assert_eq!(session.handle(r#"{"command": "read", "address": 2}"#), r#"{"ok":true,"address":2,"values":[99]}"#);
+
+        // Jumps to itself forever
+        session.handle(r#"{"command": "load", "program": "1105,1,0"}"#);
+        assert_eq!(session.handle(r#"{"command": "continue"}"#),
+                   r#"{"ok":true,"p":0,"relative_base":0,"halted":false,"event":"budget"}"#);
+    }
+
+    // Reads the requests and keeps the responses
+    struct Client {
+        requests: std::io::Cursor<Vec<u8>>,
+        responses: Vec<u8>
+    }
+
+    impl Read for Client {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            self.requests.read(buffer)
+        }
+    }
+
+    impl Write for Client {
+        fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+            self.responses.write(buffer)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_request_limit() {
+        let mut requests = format!("{}\n", r#"{"command": "state"}"#).into_bytes();
+        requests.extend(vec![b' '; REQUEST_LIMIT + 10]);
+        let mut client = Client { requests: std::io::Cursor::new(requests), responses: Vec::new() };
+
+        let result = DebugSession::new().serve_client(&mut client);
+        assert_eq!(result.map_err(|e| e.kind()), Err(std::io::ErrorKind::InvalidData));
+        assert_eq!(String::from_utf8(client.responses).unwrap(),
+                   concat!(r#"{"ok":true,"p":0,"relative_base":0,"halted":false,"event":null}"#, "\n",
+                           r#"{"ok":false,"error":"Requests can not be longer than 1048576 bytes"}"#, "\n"));
+    }
+}
@@ -0,0 +1,108 @@
+// Some simulated hardware to attach to an IntCodeComputer with attach_device
+
+use super::intcode_computer::*;
+
+/// Counts up by one every time it is read. Writing to it sets the count.
+#[derive(Debug, Clone, Default)]
+pub struct Counter {
+    pub count: isize
+}
+
+impl Device for Counter {
+    fn read(&mut self, _offset: usize) -> isize {
+        self.count += 1;
+        self.count
+    }
+
+    fn write(&mut self, _offset: usize, value: isize) {
+        self.count = value;
+    }
+}
+
+/// A width x height grid of pixels, addressed row by row
+#[derive(Debug, Clone)]
+pub struct FrameBuffer {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<isize>
+}
+
+impl FrameBuffer {
+    pub fn new(width: usize, height: usize) -> FrameBuffer {
+        FrameBuffer {
+            width,
+            height,
+            pixels: vec![0; width*height]
+        }
+    }
+
+    /// Number of addresses the frame buffer takes
+    pub fn len(&self) -> usize {
+        self.pixels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> isize {
+        self.pixels[y*self.width + x]
+    }
+
+    /// Draws the frame with '#' for the pixels that are set, like the other puzzles' canvases
+    pub fn render(&self) -> String {
+        (0..self.height).map(|row| String::from("\n") + &self.pixels[row*self.width..(row + 1)*self.width].iter()
+                                                                                                        .map(|&p| if p != 0 { '#' } else { ' ' })
+                                                                                                        .collect::<String>())
+                        .collect::<String>()
+    }
+}
+
+impl Device for FrameBuffer {
+    fn read(&mut self, offset: usize) -> isize {
+        self.pixels[offset]
+    }
+
+    fn write(&mut self, offset: usize, value: isize) {
+        self.pixels[offset] = value;
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_devices() {
+        // Reads the counter at 1000 three times, draws the pixels 0 and 3 of the frame buffer at 2000
+        // and outputs pixel 3 back
+        let mut computer = IntCodeComputer::from("4,1000,4,1000,4,1000,1101,1,0,2000,1101,0,1,2003,4,2003,99");
+        let counter = computer.attach_device(1000, 1, Counter { count: 10 });
+        let frame_buffer = computer.attach_device(2000, 4, FrameBuffer::new(2, 2));
+
+        computer.process(ReturnEvent::HaltEvent);
+
+        assert_eq!(computer.pop_all_output(), [11, 12, 13, 1]);
+        assert_eq!(counter.lock().unwrap().count, 13);
+        assert_eq!(frame_buffer.lock().unwrap().render(), "\n# \n #");
+
+        // The memory underneath is untouched
+        assert_eq!(computer.memory().len(), 17);
+    }
+
+    #[test]
+    #[should_panic(expected = "The device has 4 addresses, can not map 5 to it")]
+    fn test_device_too_short() {
+        IntCodeComputer::from("99").attach_device(2000, 5, FrameBuffer::new(2, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "The device's addresses go past the end of memory")]
+    fn test_device_past_the_end() {
+        IntCodeComputer::from("99").attach_device(usize::MAX, 2, Counter::default());
+    }
+}
@@ -0,0 +1,132 @@
+// Lets Intcode programs call into host utilities (random numbers, clock, logging, ...)
+// without abusing the input/output queues.
+//
+// A host call is the instruction: 80,id,arguments,results
+// It invokes the host function registered with the given id, with its arguments read from the
+// memory starting at the address "arguments", and writes whatever the function returns to the
+// memory starting at the address "results". All three parameters honor the parameter modes.
+// The arguments are read and the results written like any other instruction would, devices included.
+// An unknown id or a negative address halts the program on the host call.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::intcode_computer::*;
+
+pub const HOST_CALL_OPCODE: isize = 80;
+
+// Ids of the host functions in HostCalls::standard()
+pub const HOST_CALL_LOG: isize = 1;
+pub const HOST_CALL_RANDOM: isize = 2;
+pub const HOST_CALL_CLOCK: isize = 3;
+
+pub type HostFunction = Arc<dyn Fn(&[isize]) -> Vec<isize> + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub struct HostCalls {
+    // id -> (argument count, function)
+    functions: HashMap<isize, (usize, HostFunction)>
+}
+
+impl HostCalls {
+    pub fn new() -> HostCalls {
+        HostCalls::default()
+    }
+
+    /// log(value) prints the value, random(bound) returns a number in 0..bound
+    /// and clock() returns the milliseconds since the unix epoch
+    pub fn standard() -> HostCalls {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64;
+        HostCalls::standard_with_seed(seed)
+    }
+
+    /// Same as standard(), but with reproducible random numbers
+    pub fn standard_with_seed(seed: u64) -> HostCalls {
+        let mut host_calls = HostCalls::new();
+        // xorshift gets stuck on 0
+        let state = Mutex::new(seed.max(1));
+
+        host_calls.register(HOST_CALL_LOG, 1, |arguments| {
+            println!("Intcode log: {}", arguments[0]);
+            Vec::new()
+        });
+
+        host_calls.register(HOST_CALL_RANDOM, 1, move |arguments| {
+            let mut x = state.lock().unwrap();
+            *x ^= *x << 13;
+            *x ^= *x >> 7;
+            *x ^= *x << 17;
+            let bound = arguments[0].max(1) as u64;
+            vec![(*x%bound) as isize]
+        });
+
+        host_calls.register(HOST_CALL_CLOCK, 0, |_| {
+            vec![SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as isize]
+        });
+
+        host_calls
+    }
+
+    pub fn register<F>(&mut self, id: isize, argument_count: usize, function: F)
+        where F: Fn(&[isize]) -> Vec<isize> + Send + Sync + 'static {
+        self.functions.insert(id, (argument_count, Arc::new(function)));
+    }
+
+    /// Makes the host calls available to the program running on the computer
+    pub fn install(self, computer: &mut IntCodeComputer) {
+        computer.register_opcode(HOST_CALL_OPCODE, 3, &[], move |computer, parameters| {
+            let id = parameters[0];
+            let (argument_count, function) = self.functions.get(&id)
+                                                           .ok_or_else(|| format!("Unknown host call: {}", id))?;
+            let address = |parameter: isize| if parameter < 0 { Err(format!("Host call {} got the negative address {}", id, parameter)) }
+                                             else { Ok(parameter as usize) };
+            let (arguments, results) = (address(parameters[1])?, address(parameters[2])?);
+
+            let argument_values = (0..*argument_count).map(|i| computer.read_memory(arguments + i))
+                                                      .collect::<Vec<isize>>();
+
+            for (i, value) in function(&argument_values).into_iter().enumerate() {
+                computer.write_memory(results + i, value);
+            }
+
+            Ok(Vec::new())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_calls() {
+        let mut host_calls = HostCalls::standard_with_seed(42);
+        host_calls.register(10, 2, |arguments| vec![arguments[0] + arguments[1], arguments[0] * arguments[1]]);
+
+        // Calls host function 10 with the arguments at 20 and 21 and outputs the results it wrote to 22 and 23
+        // Then outputs a random number below 6 and logs it
+        let mut computer = IntCodeComputer::from("11180,10,20,22,4,22,4,23,11180,2,24,25,4,25,11180,1,25,0,99,\
+                                                  0,3,4,0,0,6,0");
+        host_calls.install(&mut computer);
+
+        // The results go where the instructions' writes go
+        computer.step();
+        assert_eq!(computer.last_writes(), [(22, 7), (23, 12)]);
+
+        computer.process(ReturnEvent::HaltEvent);
+        let output = computer.pop_all_output();
+
+        assert_eq!(output[..2], [7, 12]);
+        assert!((0..6).contains(&output[2]));
+
+        let mut computer = IntCodeComputer::from("1180,2,-1,5,99");
+        HostCalls::standard_with_seed(42).install(&mut computer);
+        assert_eq!(computer.run_with_inputs(vec![]),
+                   Err(IntCodeError::Fault { address: 0, message: String::from("Host call 2 got the negative address -1") }));
+
+        let mut computer = IntCodeComputer::from("11180,7,0,0,99");
+        HostCalls::standard_with_seed(42).install(&mut computer);
+        assert_eq!(computer.run_with_inputs(vec![]), Err(IntCodeError::Fault { address: 0, message: String::from("Unknown host call: 7") }));
+    }
+}
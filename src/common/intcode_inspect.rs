@@ -0,0 +1,125 @@
+// Tools to look inside an IntCodeComputer, for when patches like day 13's free play misbehave
+// and we need to see exactly what changed between runs.
+
+use super::intcode_computer::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryChange {
+    pub address: usize,
+    pub before: isize,
+    pub after: isize
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DumpFormat {
+    Decimal,
+    Hex
+}
+
+/// Lists the memory cells whose values differ between the two computers.
+/// Memory beyond the end of either computer's memory counts as 0.
+pub fn diff_memory(before: &IntCodeComputer, after: &IntCodeComputer) -> Vec<MemoryChange> {
+    let length = std::cmp::max(before.memory().len(), after.memory().len());
+
+    (0..length).map(|address| MemoryChange {
+                                  address,
+                                  before: before.get_memory_value(address),
+                                  after: after.get_memory_value(address)
+                              })
+               .filter(|change| change.before != change.after)
+               .collect()
+}
+
+/// Human readable summary of everything that differs between the two computers
+pub fn describe_diff(before: &IntCodeComputer, after: &IntCodeComputer) -> String {
+    let mut lines = Vec::new();
+
+    if before.program_counter() != after.program_counter() {
+        lines.push(format!("p: {} -> {}", before.program_counter(), after.program_counter()));
+    }
+
+    if before.relative_base() != after.relative_base() {
+        lines.push(format!("relative_base: {} -> {}", before.relative_base(), after.relative_base()));
+    }
+
+    for change in diff_memory(before, after) {
+        lines.push(format!("[{}]: {} -> {}", change.address, change.before, change.after));
+    }
+
+    lines.join("\n")
+}
+
+fn format_value(value: isize, format: DumpFormat) -> String {
+    match format {
+        DumpFormat::Decimal => value.to_string(),
+        DumpFormat::Hex if value < 0 => format!("-{:x}", -(value as i128)),
+        DumpFormat::Hex => format!("{:x}", value)
+    }
+}
+
+/// Dumps the memory as a table with `columns` values per row.
+/// The cell at p is marked with '>' and the cell at the relative base with '@'.
+pub fn dump_memory(computer: &IntCodeComputer, format: DumpFormat, columns: usize) -> String {
+    assert!(columns > 0);
+
+    let memory = computer.memory();
+    let p = computer.program_counter();
+    let relative_base = computer.relative_base();
+    let width = memory.iter()
+                      .map(|&value| format_value(value, format).len())
+                      .max()
+                      .unwrap_or(1);
+    let address_width = format_value(memory.len() as isize, format).len();
+
+    let mut lines = vec![format!("p = {} (>), relative_base = {} (@)", p, relative_base)];
+
+    for (row, values) in memory.chunks(columns).enumerate() {
+        let cells = values.iter()
+                          .enumerate()
+                          .map(|(column, &value)| {
+                              let address = row*columns + column;
+                              let marker = if address == p { '>' }
+                                           else if address as isize == relative_base { '@' }
+                                           else { ' ' };
+                              format!("{}{:>width$}", marker, format_value(value, format), width = width)
+                          })
+                          .collect::<Vec<String>>()
+                          .join(" ");
+
+        lines.push(format!("{:>width$}: {}", format_value((row*columns) as isize, format), cells, width = address_width));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let before = IntCodeComputer::from("1,0,0,0,99");
+        let mut after = before.clone();
+        after.process(ReturnEvent::HaltEvent);
+        after.set_memory_value(6, -1);
+
+        assert_eq!(diff_memory(&before, &after), [MemoryChange { address: 0, before: 1, after: 2 },
+                                                  MemoryChange { address: 6, before: 0, after: -1 }]);
+        assert_eq!(describe_diff(&before, &after), "p: 0 -> 4\n[0]: 1 -> 2\n[6]: 0 -> -1");
+    }
+
+    #[test]
+    fn test_dump() {
+        let mut computer = IntCodeComputer::from("109,3,1101,7,-26,0,99");
+        computer.step();
+
+        assert_eq!(dump_memory(&computer, DumpFormat::Decimal, 4),
+                   "p = 2 (>), relative_base = 3 (@)\n\
+                    0:   109     3 >1101 @   7\n\
+                    4:   -26     0    99");
+        assert_eq!(dump_memory(&computer, DumpFormat::Hex, 4),
+                   "p = 2 (>), relative_base = 3 (@)\n\
+                    0:   6d    3 >44d @  7\n\
+                    4:  -1a    0   63");
+    }
+}
@@ -0,0 +1,170 @@
+// Loads Intcode programs from more than the single comma separated line that IntCodeComputer::from accepts.
+//
+// The text format takes comma and/or whitespace separated values over any number of lines.
+// Everything after a '#' is a comment, except for header lines that set the program's metadata:
+//   #! name: Diagnostic program
+//   #! inputs: 1
+//
+// The binary format is just the values as little endian 64 bit words, one after the other.
+
+use std::fmt;
+use std::path::Path;
+
+use super::intcode_computer::*;
+use super::parse_error::*;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Program {
+    pub name: Option<String>,
+    pub expected_inputs: Option<usize>,
+    pub memory: Vec<isize>
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    Parse(ParseError)
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "{}", error),
+            LoadError::Parse(error) => write!(f, "{}", error)
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+fn parse_header(program: &mut Program, header: &str, line: usize, column: usize) -> Result<(), ParseError> {
+    let mut parts = header.splitn(2, ':');
+    let key = parts.next().unwrap().trim();
+    let value = parts.next()
+                     .ok_or_else(|| ParseError::new(line, column, "Expected a header like \"#! key: value\""))?
+                     .trim();
+
+    match key {
+        "name" => program.name = Some(String::from(value)),
+        "inputs" => program.expected_inputs = Some(value.parse::<usize>()
+                                                        .map_err(|_| ParseError::new(line, column, "Expected the number of inputs"))?),
+        _ => return Err(ParseError::new(line, column, &format!("Unknown header: {}", key)))
+    }
+
+    Ok(())
+}
+
+/// Parses the text format
+pub fn parse_program(text: &str) -> Result<Program, ParseError> {
+    let mut program = Program::default();
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let (code, comment) = match line.find('#') {
+            Some(i) => (&line[..i], Some(&line[i..])),
+            None => (line, None)
+        };
+
+        if let Some(header) = comment.and_then(|comment| comment.strip_prefix("#!")) {
+            let column = line[..line.find('#').unwrap()].chars().count() + 1;
+            parse_header(&mut program, header, line_number, column)?;
+        }
+
+        // Tokens are whatever is between commas and whitespace
+        let mut token_start = None;
+
+        for (column, c) in code.chars().chain(std::iter::once(' ')).enumerate() {
+            if c == ',' || c.is_whitespace() {
+                if let Some(start) = token_start.take() {
+                    let token = code.chars().skip(start).take(column - start).collect::<String>();
+                    let value = token.parse::<isize>()
+                                     .map_err(|_| ParseError::new(line_number, start + 1, &format!("Expected an integer, got: {}", token)))?;
+                    program.memory.push(value);
+                }
+            } else if token_start.is_none() {
+                token_start = Some(column);
+            }
+        }
+    }
+
+    Ok(program)
+}
+
+/// Parses the binary format. The column of an error is the byte offset in the data.
+pub fn parse_binary(bytes: &[u8]) -> Result<Program, ParseError> {
+    if bytes.len() % 8 != 0 {
+        return Err(ParseError::new(1, bytes.len() - bytes.len()%8 + 1, "Incomplete 64 bit word at the end"));
+    }
+
+    let mut program = Program::default();
+    for word in bytes.chunks(8) {
+        let mut buffer = [0; 8];
+        buffer.copy_from_slice(word);
+        program.memory.push(i64::from_le_bytes(buffer) as isize);
+    }
+
+    Ok(program)
+}
+
+pub fn to_binary(memory: &[isize]) -> Vec<u8> {
+    memory.iter()
+          .flat_map(|&value| (value as i64).to_le_bytes().to_vec())
+          .collect()
+}
+
+/// Loads a program, in the binary format if the file ends with .bin or .icb and in the text format otherwise
+pub fn load_program<P: AsRef<Path>>(path: P) -> Result<Program, LoadError> {
+    let path = path.as_ref();
+    let is_binary = path.extension()
+                        .is_some_and(|extension| extension == "bin" || extension == "icb");
+
+    if is_binary {
+        let bytes = std::fs::read(path).map_err(LoadError::Io)?;
+        parse_binary(&bytes).map_err(LoadError::Parse)
+    } else {
+        let text = std::fs::read_to_string(path).map_err(LoadError::Io)?;
+        parse_program(&text).map_err(LoadError::Parse)
+    }
+}
+
+impl From<Program> for IntCodeComputer {
+    fn from(program: Program) -> Self {
+        IntCodeComputer::from(program.memory)
+    }
+}
+
+/// For running the same program more than once
+impl From<&Program> for IntCodeComputer {
+    fn from(program: &Program) -> Self {
+        IntCodeComputer::from(program.memory.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_program() {
+        assert_eq!(parse_program("1,0,0,0,99").unwrap().memory, [1, 0, 0, 0, 99]);
+
+        let program = parse_program("#! name: Echo\n\
+                                     #! inputs: 1\n\
+                                     3,0,   # read\n\
+                                     4 0    # write\n\
+                                     \n\
+                                     99\n").unwrap();
+        assert_eq!(program, Program { name: Some(String::from("Echo")), expected_inputs: Some(1), memory: vec![3, 0, 4, 0, 99] });
+
+        assert_eq!(parse_program("1,0,0,0\n99,x1,2"), Err(ParseError::new(2, 4, "Expected an integer, got: x1")));
+        assert_eq!(parse_program("#! size: 4"), Err(ParseError::new(1, 1, "Unknown header: size")));
+    }
+
+    #[test]
+    fn test_parse_binary() {
+        let memory = vec![1102, 34915192, -34915192, 7, 4, 7, 99, 0];
+        assert_eq!(parse_binary(&to_binary(&memory)).unwrap().memory, memory);
+
+        assert_eq!(parse_binary(&[1, 0, 0, 0, 0, 0, 0, 0, 99]), Err(ParseError::new(1, 9, "Incomplete 64 bit word at the end")));
+    }
+}
@@ -0,0 +1,195 @@
+// Cheat codes for Intcode programs, like day 13's free play (setting address 0 to 2).
+//
+// A patch file lists the memory cells to overwrite, one per line:
+//   # Free play
+//   0 = 2
+//   # Replace the instruction at 1000 with a jump to 2000
+//   1000: 1105, 1, 2000
+//
+// CellSearch helps finding new patches: it keeps memory snapshots along with a value observed at the same
+// time (the score, the lives left...) and finds the cells that follow that value.
+
+use std::fmt;
+
+use super::intcode_computer::*;
+use super::parse_error::*;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Patch {
+    /// (address, values to write starting at the address)
+    pub writes: Vec<(usize, Vec<isize>)>
+}
+
+fn parse_value(text: &str, line: usize, column: usize) -> Result<isize, ParseError> {
+    text.trim()
+        .parse::<isize>()
+        .map_err(|_| ParseError::new(line, column, &format!("Expected an integer, got: {}", text.trim())))
+}
+
+impl Patch {
+    pub fn parse(text: &str) -> Result<Patch, ParseError> {
+        let mut patch = Patch::default();
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line_number = line_index + 1;
+            let code = line.split('#').next().unwrap();
+
+            if code.trim().is_empty() {
+                continue;
+            }
+
+            let separator = code.find(['=', ':'])
+                                .ok_or_else(|| ParseError::new(line_number, 1, "Expected \"address = value\" or \"address: values\""))?;
+            let address = code[..separator].trim()
+                                           .parse::<usize>()
+                                           .map_err(|_| ParseError::new(line_number, 1, "Expected an address"))?;
+
+            let mut values = Vec::new();
+            let mut column = separator + 2;
+
+            for item in code[separator + 1..].split(',') {
+                values.push(parse_value(item, line_number, column + item.len() - item.trim_start().len())?);
+                column += item.len() + 1;
+            }
+
+            if code[separator..].starts_with('=') && values.len() != 1 {
+                return Err(ParseError::new(line_number, separator + 1, "\"address = value\" takes a single value"));
+            }
+
+            patch.writes.push((address, values));
+        }
+
+        Ok(patch)
+    }
+
+    pub fn apply(&self, computer: &mut IntCodeComputer) {
+        for (address, values) in self.writes.iter() {
+            for (i, &value) in values.iter().enumerate() {
+                computer.set_memory_value(address + i, value);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Patch {
+    /// Writes the patch in the same format it is parsed from
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (address, values) in self.writes.iter() {
+            if values.len() == 1 {
+                writeln!(f, "{} = {}", address, values[0])?;
+            } else {
+                writeln!(f, "{}: {}", address, values.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CellSearch {
+    // (memory snapshot, observed value)
+    samples: Vec<(Vec<isize>, isize)>
+}
+
+impl CellSearch {
+    pub fn new() -> CellSearch {
+        CellSearch::default()
+    }
+
+    /// Records the computer's memory along with the value observed from its outputs at that moment
+    pub fn observe(&mut self, computer: &IntCodeComputer, observed: isize) {
+        self.samples.push((computer.memory().to_vec(), observed));
+    }
+
+    fn cell_count(&self) -> usize {
+        self.samples.iter().map(|(memory, _)| memory.len()).max().unwrap_or(0)
+    }
+
+    fn cell_values(&self, address: usize) -> Vec<isize> {
+        self.samples.iter()
+                    .map(|(memory, _)| *memory.get(address).unwrap_or(&0))
+                    .collect()
+    }
+
+    /// Cells that held exactly the observed value every time
+    pub fn matching_cells(&self) -> Vec<usize> {
+        (0..self.cell_count()).filter(|&address| self.cell_values(address).iter()
+                                                                          .zip(self.samples.iter())
+                                                                          .all(|(value, (_, observed))| value == observed))
+                              .collect()
+    }
+
+    /// Cells whose values correlate with the observed values (by the Pearson correlation coefficient),
+    /// strongest first. Catches cells that store the value with an offset or a scale, like lives - 1.
+    pub fn correlated_cells(&self, threshold: f64) -> Vec<(usize, f64)> {
+        let observed = self.samples.iter().map(|&(_, value)| value).collect::<Vec<isize>>();
+
+        let mut result = (0..self.cell_count()).filter_map(|address| {
+                                                   let correlation = pearson(&self.cell_values(address), &observed)?;
+                                                   if correlation.abs() >= threshold { Some((address, correlation)) } else { None }
+                                               })
+                                               .collect::<Vec<(usize, f64)>>();
+
+        result.sort_by(|a, b| b.1.abs().partial_cmp(&a.1.abs()).unwrap().then(a.0.cmp(&b.0)));
+        result
+    }
+}
+
+// None if either of the series never changes, as the correlation is meaningless then.
+// Summed as floats, as memory cells can hold values whose sum overflows
+fn pearson(xs: &[isize], ys: &[isize]) -> Option<f64> {
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().map(|&x| x as f64).sum::<f64>()/n;
+    let mean_y = ys.iter().map(|&y| y as f64).sum::<f64>()/n;
+
+    let covariance = xs.iter().zip(ys.iter()).map(|(&x, &y)| (x as f64 - mean_x)*(y as f64 - mean_y)).sum::<f64>();
+    let variance_x = xs.iter().map(|&x| (x as f64 - mean_x).powi(2)).sum::<f64>();
+    let variance_y = ys.iter().map(|&y| (y as f64 - mean_y).powi(2)).sum::<f64>();
+
+    if variance_x == 0.0 || variance_y == 0.0 {
+        None
+    } else {
+        Some(covariance/(variance_x*variance_y).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch() {
+        let patch = Patch::parse("# Free play\n\
+                                  0 = 2\n\
+                                  4: 1105, 1, 9   # Skip ahead\n").unwrap();
+        assert_eq!(patch.writes, [(0, vec![2]), (4, vec![1105, 1, 9])]);
+        assert_eq!(Patch::parse(&patch.to_string()).unwrap(), patch);
+
+        let mut computer = IntCodeComputer::from("1,0,0,0,99");
+        patch.apply(&mut computer);
+        assert_eq!(computer.memory(), [2, 0, 0, 0, 1105, 1, 9]);
+
+        assert_eq!(Patch::parse("0 = 2\n12: 1, x"), Err(ParseError::new(2, 8, "Expected an integer, got: x")));
+        assert_eq!(Patch::parse("0 = 2, 3"), Err(ParseError::new(1, 3, "\"address = value\" takes a single value")));
+        assert_eq!(Patch::parse("zero = 2"), Err(ParseError::new(1, 1, "Expected an address")));
+    }
+
+    #[test]
+    fn test_cell_search() {
+        // Adds every input to a score at 20 and keeps "lives" at 21 as 3 - score
+        let mut computer = IntCodeComputer::from("3,22,1,20,22,20,1002,20,-1,21,1001,21,3,21,4,20,1105,1,0");
+        let mut search = CellSearch::new();
+
+        for input in 1..5 {
+            computer.push_input(input);
+            let score = computer.outputs().next().unwrap();
+            search.observe(&computer, score);
+        }
+
+        assert_eq!(search.matching_cells(), [20]);
+        assert_eq!(search.correlated_cells(0.99).iter().map(|&(address, _)| address).collect::<Vec<usize>>(), [20, 21]);
+
+        assert!(pearson(&[isize::MAX, isize::MAX, 0], &[1, 1, 0]).unwrap() > 0.99);
+    }
+}
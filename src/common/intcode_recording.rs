@@ -0,0 +1,177 @@
+// Records the inputs an IntCodeComputer consumes and the outputs it produces, along with the instruction
+// count at which each happened, so that an interesting session (a day 13 game, a day 15 path through the maze...)
+// can be replayed later to check that the program still behaves exactly the same.
+//
+// The text format has one event per line, "in" or "out", the instruction count and the value:
+//   # Moves north once
+//   in 5 1
+//   out 24 0
+
+use std::fmt;
+
+use super::intcode_computer::*;
+use super::parse_error::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordedEvent {
+    /// The input was consumed by the instruction executed after `at` instructions
+    Input { at: usize, value: isize },
+    /// The output was produced by the instruction executed after `at` instructions
+    Output { at: usize, value: isize }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Recording {
+    pub events: Vec<RecordedEvent>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayError {
+    /// The program produced something else than the recorded event, or produced it at another time
+    Mismatch { index: usize, expected: Option<RecordedEvent>, actual: RecordedEvent },
+    /// The program asked for an input before the recording gave it one
+    InputNeeded { index: usize, at: usize },
+    /// The recording gives an input when the program is about to run some other instruction
+    NotAnInput { index: usize, at: usize, opcode: isize },
+    /// The program halted before everything that was recorded happened
+    Halted { index: usize }
+}
+
+impl Recording {
+    pub fn inputs(&self) -> Vec<isize> {
+        self.events.iter()
+                   .filter_map(|event| match event { RecordedEvent::Input { value, .. } => Some(*value), _ => None })
+                   .collect()
+    }
+
+    pub fn outputs(&self) -> Vec<isize> {
+        self.events.iter()
+                   .filter_map(|event| match event { RecordedEvent::Output { value, .. } => Some(*value), _ => None })
+                   .collect()
+    }
+
+    pub fn parse(text: &str) -> Result<Recording, ParseError> {
+        let mut recording = Recording::default();
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line_number = line_index + 1;
+            let code = line.split('#').next().unwrap();
+
+            if code.trim().is_empty() {
+                continue;
+            }
+
+            let items = code.split_whitespace().collect::<Vec<&str>>();
+            if items.len() != 3 {
+                return Err(ParseError::new(line_number, 1, "Expected \"in|out <instruction count> <value>\""));
+            }
+
+            let column = |item: &str| item.as_ptr() as usize - code.as_ptr() as usize + 1;
+            let at = items[1].parse::<usize>()
+                             .map_err(|_| ParseError::new(line_number, column(items[1]), &format!("Expected an instruction count, got: {}", items[1])))?;
+            let value = items[2].parse::<isize>()
+                                .map_err(|_| ParseError::new(line_number, column(items[2]), &format!("Expected an integer, got: {}", items[2])))?;
+
+            recording.events.push(match items[0] {
+                "in" => RecordedEvent::Input { at, value },
+                "out" => RecordedEvent::Output { at, value },
+                kind => return Err(ParseError::new(line_number, column(kind), &format!("Expected in or out, got: {}", kind)))
+            });
+        }
+
+        Ok(recording)
+    }
+}
+
+impl fmt::Display for Recording {
+    /// Writes the recording in the same format it is parsed from
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for event in self.events.iter() {
+            match event {
+                RecordedEvent::Input { at, value } => writeln!(f, "in {} {}", at, value)?,
+                RecordedEvent::Output { at, value } => writeln!(f, "out {} {}", at, value)?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs the computer on the recorded inputs, each given right when it was consumed in the recording,
+/// and checks that every recorded output comes out with the same value at the same time.
+/// The replay succeeds when the computer halts, or asks for more input, right after the last recorded event.
+/// The outputs are taken out of the computer's output queue along the way.
+pub fn replay(computer: &mut IntCodeComputer, recording: &Recording) -> Result<(), ReplayError> {
+    let mut events = recording.events.iter().copied().enumerate().peekable();
+
+    loop {
+        if let Some(&(index, RecordedEvent::Input { at, value })) = events.peek() {
+            if at == computer.instruction_count() {
+                let opcode = computer.get_memory_value(computer.program_counter());
+                if opcode%100 != 3 {
+                    return Err(ReplayError::NotAnInput { index, at, opcode });
+                }
+                computer.push_input(value);
+                events.next();
+            }
+        }
+
+        let at = computer.instruction_count();
+
+        match computer.step() {
+            Some(ReturnEvent::OutputReadyEvent) => {
+                // Older outputs may still be in the queue, the new one is the last
+                let actual = RecordedEvent::Output { at, value: *computer.pop_all_output().last().unwrap() };
+
+                match events.next() {
+                    Some((_, expected)) if expected == actual => (),
+                    Some((index, expected)) => return Err(ReplayError::Mismatch { index, expected: Some(expected), actual }),
+                    None => return Err(ReplayError::Mismatch { index: recording.events.len(), expected: None, actual })
+                }
+            },
+            // A session can end with the program waiting for the next input
+            Some(ReturnEvent::InputNeededEvent) if events.peek().is_none() => return Ok(()),
+            Some(ReturnEvent::InputNeededEvent) => return Err(ReplayError::InputNeeded { index: events.peek().unwrap().0, at }),
+            Some(ReturnEvent::HaltEvent) => break,
+            None => ()
+        }
+    }
+
+    match events.next() {
+        Some((index, _)) => Err(ReplayError::Halted { index }),
+        None => Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_replay() {
+        // Outputs the double of every input until it gets a 0
+        let program = "3,15,1006,15,14,1002,15,2,15,4,15,1105,1,0,99";
+
+        let mut computer = IntCodeComputer::from(program);
+        computer.start_recording();
+        assert_eq!(computer.run_with_inputs(vec![3, 5, 0]), Ok(vec![6, 10]));
+
+        let recording = computer.take_recording().unwrap();
+        assert_eq!(recording.inputs(), [3, 5, 0]);
+        assert_eq!(recording.outputs(), [6, 10]);
+        assert_eq!(Recording::parse(&recording.to_string()), Ok(recording.clone()));
+
+        assert_eq!(replay(&mut IntCodeComputer::from(program), &recording), Ok(()));
+
+        // Doubling became tripling
+        let changed = program.replacen("1002,15,2", "1002,15,3", 1);
+        assert_eq!(replay(&mut IntCodeComputer::from(changed.as_str()), &recording),
+                   Err(ReplayError::Mismatch { index: 1, expected: Some(recording.events[1]), actual: RecordedEvent::Output { at: 3, value: 9 } }));
+
+        // The second input comes while the program is doubling the first one
+        assert_eq!(replay(&mut IntCodeComputer::from(program), &Recording::parse("in 0 3\nin 2 5").unwrap()),
+                   Err(ReplayError::NotAnInput { index: 1, at: 2, opcode: 1002 }));
+
+        assert_eq!(Recording::parse("in 1 2\nout 3 x"), Err(ParseError::new(2, 7, "Expected an integer, got: x")));
+    }
+}
@@ -0,0 +1,192 @@
+// Writes a record for every instruction an IntCodeComputer executes, so that a run can be loaded into
+// a notebook or a plotting tool instead of sprinkling print_canvas calls around.
+//
+// Two formats are available, JSON Lines (one JSON object per line):
+//   {"step":0,"address":0,"opcode":1,"modes":0,"operands":[5,6,7],"writes":[[7,3]],"relative_base":0}
+// and CSV, where the lists are space separated and each write is address:value:
+//   step,address,opcode,modes,operands,writes,relative_base
+//   0,0,1,0,5 6 7,7:3,0
+
+use std::io::{self, Write};
+
+use super::intcode_computer::*;
+use super::json::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceRecord {
+    /// Number of instructions executed before this one
+    pub step: usize,
+    pub address: usize,
+    pub opcode: isize,
+    /// The parameter modes, as the digits above the opcode
+    pub modes: isize,
+    /// The raw parameters, before the modes are applied
+    pub operands: Vec<isize>,
+    /// (address, value) of everything the instruction wrote
+    pub writes: Vec<(usize, isize)>,
+    /// The relative base the instruction ran with
+    pub relative_base: isize
+}
+
+pub trait TraceSink {
+    fn record(&mut self, record: &TraceRecord) -> io::Result<()>;
+}
+
+// So that the format can be picked at runtime
+impl<T: TraceSink + ?Sized> TraceSink for Box<T> {
+    fn record(&mut self, record: &TraceRecord) -> io::Result<()> {
+        (**self).record(record)
+    }
+}
+
+pub struct JsonLinesSink<W: Write> {
+    writer: W
+}
+
+impl<W: Write> JsonLinesSink<W> {
+    pub fn new(writer: W) -> JsonLinesSink<W> {
+        JsonLinesSink { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> TraceSink for JsonLinesSink<W> {
+    fn record(&mut self, record: &TraceRecord) -> io::Result<()> {
+        let writes = record.writes.iter()
+                                  .map(|&(address, value)| JsonValue::Array(vec![address.into(), value.into()]))
+                                  .collect::<Vec<JsonValue>>();
+        let value = JsonValue::object(vec![("step", record.step.into()),
+                                           ("address", record.address.into()),
+                                           ("opcode", record.opcode.into()),
+                                           ("modes", record.modes.into()),
+                                           ("operands", record.operands.clone().into()),
+                                           ("writes", JsonValue::Array(writes)),
+                                           ("relative_base", record.relative_base.into())]);
+
+        writeln!(self.writer, "{}", value)
+    }
+}
+
+pub struct CsvSink<W: Write> {
+    writer: W,
+    has_header: bool
+}
+
+impl<W: Write> CsvSink<W> {
+    pub fn new(writer: W) -> CsvSink<W> {
+        CsvSink { writer, has_header: false }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> TraceSink for CsvSink<W> {
+    fn record(&mut self, record: &TraceRecord) -> io::Result<()> {
+        if !self.has_header {
+            writeln!(self.writer, "step,address,opcode,modes,operands,writes,relative_base")?;
+            self.has_header = true;
+        }
+
+        writeln!(self.writer, "{},{},{},{},{},{},{}",
+                 record.step,
+                 record.address,
+                 record.opcode,
+                 record.modes,
+                 record.operands.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(" "),
+                 record.writes.iter().map(|(address, value)| format!("{}:{}", address, value)).collect::<Vec<String>>().join(" "),
+                 record.relative_base)
+    }
+}
+
+/// Drives a computer like its own step and process do, sending a record of every executed instruction to the sink
+pub struct Tracer<S: TraceSink> {
+    sink: S
+}
+
+impl<S: TraceSink> Tracer<S> {
+    pub fn new(sink: S) -> Tracer<S> {
+        Tracer { sink }
+    }
+
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+
+    pub fn step(&mut self, computer: &mut IntCodeComputer) -> io::Result<Option<ReturnEvent>> {
+        let step = computer.instruction_count();
+        let address = computer.program_counter();
+        let relative_base = computer.relative_base();
+        let instruction = computer.get_memory_value(address);
+        let arity = computer.opcode_arity(instruction%100).unwrap_or(0);
+        // Read before executing, as the instruction may overwrite its own operands
+        let operands = (1..=arity).map(|i| computer.get_memory_value(address + i)).collect();
+
+        let return_event = computer.step();
+
+        // Nothing was executed if the computer was waiting for input or had halted already
+        if computer.instruction_count() > step {
+            self.sink.record(&TraceRecord {
+                step,
+                address,
+                opcode: instruction%100,
+                modes: instruction/100,
+                operands,
+                writes: computer.last_writes().to_vec(),
+                relative_base
+            })?;
+        }
+
+        Ok(return_event)
+    }
+
+    /// Same as IntCodeComputer::process
+    pub fn process(&mut self, computer: &mut IntCodeComputer, return_event: ReturnEvent) -> io::Result<bool> {
+        while !computer.is_halted() && computer.program_counter() < computer.memory().len() {
+            match self.step(computer)? {
+                Some(ReturnEvent::InputNeededEvent) => break,
+                Some(ReturnEvent::OutputReadyEvent) if return_event == ReturnEvent::OutputReadyEvent => break,
+                _ => ()
+            }
+        }
+
+        Ok(computer.is_halted())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace() {
+        let mut computer = IntCodeComputer::from("109,10,21101,2,3,0,4,10,99");
+        let mut tracer = Tracer::new(CsvSink::new(Vec::new()));
+        assert!(tracer.process(&mut computer, ReturnEvent::HaltEvent).unwrap());
+
+        assert_eq!(String::from_utf8(tracer.into_sink().into_inner()).unwrap(),
+                   "step,address,opcode,modes,operands,writes,relative_base\n\
+                    0,0,9,1,10,,0\n\
+                    1,2,1,211,2 3 0,10:5,10\n\
+                    2,6,4,0,10,,10\n\
+                    3,8,99,0,,,10\n");
+
+        let mut computer = IntCodeComputer::from("1101,2,3,5,99");
+        let mut tracer = Tracer::new(JsonLinesSink::new(Vec::new()));
+        tracer.process(&mut computer, ReturnEvent::HaltEvent).unwrap();
+
+        let text = String::from_utf8(tracer.into_sink().into_inner()).unwrap();
+        let records = text.lines().map(|line| parse(line).unwrap()).collect::<Vec<JsonValue>>();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].to_string(), "{\"step\":0,\"address\":0,\"opcode\":1,\"modes\":11,\"operands\":[2,3,5],\"writes\":[[5,5]],\"relative_base\":0}");
+        assert_eq!(records[1].get("opcode").and_then(|opcode| opcode.as_i64()), Some(99));
+    }
+}
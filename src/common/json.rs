@@ -0,0 +1,377 @@
+// Just enough JSON to talk to tools written in other languages,
+// without pulling in a serialization framework for it.
+
+use std::fmt;
+
+// Deeper documents are rejected rather than risking the parser's recursion overflowing the stack
+const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    // Keeping the keys in insertion order makes the output predictable
+    Object(Vec<(String, JsonValue)>)
+}
+
+impl JsonValue {
+    pub fn object(fields: Vec<(&str, JsonValue)>) -> JsonValue {
+        JsonValue::Object(fields.into_iter()
+                                .map(|(key, value)| (String::from(key), value))
+                                .collect())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            JsonValue::Integer(value) => Some(*value),
+            _ => None
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(value) => Some(value),
+            _ => None
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<JsonValue>> {
+        match self {
+            JsonValue::Array(values) => Some(values),
+            _ => None
+        }
+    }
+}
+
+impl From<isize> for JsonValue {
+    fn from(value: isize) -> Self {
+        JsonValue::Integer(value as i64)
+    }
+}
+
+impl From<usize> for JsonValue {
+    fn from(value: usize) -> Self {
+        JsonValue::Integer(value as i64)
+    }
+}
+
+impl From<bool> for JsonValue {
+    fn from(value: bool) -> Self {
+        JsonValue::Bool(value)
+    }
+}
+
+impl From<&str> for JsonValue {
+    fn from(value: &str) -> Self {
+        JsonValue::String(String::from(value))
+    }
+}
+
+impl From<Vec<isize>> for JsonValue {
+    fn from(values: Vec<isize>) -> Self {
+        JsonValue::Array(values.into_iter().map(JsonValue::from).collect())
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, text: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in text.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for JsonValue {
+    /// Writes the value as compact, single line JSON
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(value) => write!(f, "{}", value),
+            JsonValue::Integer(value) => write!(f, "{}", value),
+            JsonValue::Float(value) => if value.is_finite() { write!(f, "{}", value) } else { write!(f, "null") },
+            JsonValue::String(value) => write_string(f, value),
+            JsonValue::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            },
+            JsonValue::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Parses a single JSON document
+pub fn parse(text: &str) -> Result<JsonValue, String> {
+    let mut parser = Parser { chars: text.chars().collect(), position: 0, depth: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+
+    if parser.position < parser.chars.len() {
+        return Err(format!("Unexpected trailing characters at {}", parser.position));
+    }
+
+    Ok(value)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    // Arrays and objects we are inside of
+    depth: usize
+}
+
+impl Parser {
+    fn skip_whitespace(&mut self) {
+        while self.position < self.chars.len() && self.chars[self.position].is_whitespace() {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(format!("Expected '{}' at {}", c, self.position))
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: JsonValue) -> Result<JsonValue, String> {
+        let end = self.position + word.len();
+        if end <= self.chars.len() && self.chars[self.position..end].iter().collect::<String>() == word {
+            self.position = end;
+            Ok(value)
+        } else {
+            Err(format!("Unexpected token at {}", self.position))
+        }
+    }
+
+    fn value(&mut self) -> Result<JsonValue, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if (c == '{' || c == '[') && self.depth == MAX_DEPTH => {
+                Err(format!("Nested deeper than {} levels at {}", MAX_DEPTH, self.position))
+            },
+            Some(c) if c == '{' || c == '[' => {
+                self.depth += 1;
+                let value = if c == '{' { self.object() } else { self.array() };
+                self.depth -= 1;
+                value
+            },
+            Some('"') => Ok(JsonValue::String(self.string()?)),
+            Some('t') => self.keyword("true", JsonValue::Bool(true)),
+            Some('f') => self.keyword("false", JsonValue::Bool(false)),
+            Some('n') => self.keyword("null", JsonValue::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(_) => Err(format!("Unexpected character at {}", self.position)),
+            None => Err(String::from("Unexpected end of input"))
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonValue, String> {
+        let mut fields = Vec::new();
+        self.expect('{')?;
+        self.skip_whitespace();
+
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(JsonValue::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Object(fields));
+                },
+                _ => return Err(format!("Expected ',' or '}}' at {}", self.position))
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<JsonValue, String> {
+        let mut values = Vec::new();
+        self.expect('[')?;
+        self.skip_whitespace();
+
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(JsonValue::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Array(values));
+                },
+                _ => return Err(format!("Expected ',' or ']' at {}", self.position))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.peek() != Some('"') {
+            return Err(format!("Expected a string at {}", self.position));
+        }
+        self.position += 1;
+
+        let mut result = String::new();
+
+        loop {
+            let c = self.peek().ok_or_else(|| String::from("Unterminated string"))?;
+            self.position += 1;
+
+            match c {
+                '"' => return Ok(result),
+                '\\' => {
+                    let escaped = self.peek().ok_or_else(|| String::from("Unterminated string"))?;
+                    self.position += 1;
+                    match escaped {
+                        'n' => result.push('\n'),
+                        'r' => result.push('\r'),
+                        't' => result.push('\t'),
+                        'b' => result.push('\u{8}'),
+                        'f' => result.push('\u{c}'),
+                        'u' => {
+                            let code = self.hex_escape()?;
+
+                            // Characters outside the basic plane come as a pair of UTF-16 surrogates, \uD83D\uDE00
+                            if (0xd800..0xdc00).contains(&code) && self.chars[self.position..].starts_with(&['\\', 'u']) {
+                                self.position += 2;
+                                let low = self.hex_escape()?;
+
+                                if (0xdc00..0xe000).contains(&low) {
+                                    result.push(std::char::from_u32(0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00)).unwrap());
+                                } else {
+                                    result.push('\u{fffd}');
+                                    result.push(std::char::from_u32(low).unwrap_or('\u{fffd}'));
+                                }
+                            } else {
+                                result.push(std::char::from_u32(code).unwrap_or('\u{fffd}'));
+                            }
+                        },
+                        c => result.push(c)
+                    }
+                },
+                c => result.push(c)
+            }
+        }
+    }
+
+    // The 4 hex digits after \u
+    fn hex_escape(&mut self) -> Result<u32, String> {
+        let end = self.position + 4;
+        if end > self.chars.len() {
+            return Err(String::from("Unterminated string"));
+        }
+        let code = self.chars[self.position..end].iter().collect::<String>();
+        let code = u32::from_str_radix(&code, 16).map_err(|_| format!("Invalid escape at {}", self.position))?;
+        self.position = end;
+        Ok(code)
+    }
+
+    fn number(&mut self) -> Result<JsonValue, String> {
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() || "+-.eE".contains(c) {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+
+        let text = self.chars[start..self.position].iter().collect::<String>();
+
+        if let Ok(value) = text.parse::<i64>() {
+            Ok(JsonValue::Integer(value))
+        } else {
+            text.parse::<f64>()
+                .map(JsonValue::Float)
+                .map_err(|_| format!("Invalid number at {}", start))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let value = parse(r#" {"command": "read", "address": -12, "values": [1, 2.5, true, null], "text": "a\"b\n"} "#).unwrap();
+
+        assert_eq!(value.get("command").and_then(|v| v.as_str()), Some("read"));
+        assert_eq!(value.get("address").and_then(|v| v.as_i64()), Some(-12));
+        assert_eq!(value.get("values"), Some(&JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Float(2.5),
+                                                                    JsonValue::Bool(true), JsonValue::Null])));
+        assert_eq!(value.get("text").and_then(|v| v.as_str()), Some("a\"b\n"));
+
+        assert!(parse("{\"a\": 1").is_err());
+        assert!(parse("[1, 2] 3").is_err());
+
+        assert_eq!(parse(r#""\u00e9\uD83D\uDE00\uD83D""#), Ok(JsonValue::from("\u{e9}\u{1f600}\u{fffd}")));
+        assert_eq!(parse(&"[".repeat(100_000)), Err(String::from("Nested deeper than 128 levels at 128")));
+        assert!(parse(&("[".repeat(128) + &"]".repeat(128))).is_ok());
+    }
+
+    #[test]
+    fn test_display() {
+        let value = JsonValue::object(vec![("ok", JsonValue::from(true)),
+                                           ("values", JsonValue::from(vec![1, -2])),
+                                           ("error", JsonValue::from("tab\there"))]);
+
+        assert_eq!(value.to_string(), r#"{"ok":true,"values":[1,-2],"error":"tab\there"}"#);
+        assert_eq!(parse(&value.to_string()).unwrap(), value);
+    }
+}
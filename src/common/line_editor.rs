@@ -0,0 +1,342 @@
+// Reads lines from a terminal with the cursor keys working: left/right move in the line,
+// up/down go through the history, home/end (or Ctrl-A/Ctrl-E) jump to either end.
+// The terminal is switched out of its line buffered (cooked) mode with stty only while a line is read,
+// so that Ctrl-C still stops a running program. When stdin isn't a terminal lines are read as they are.
+
+use std::io::{self, BufRead, Read, Write};
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    /// Ctrl-U
+    DeleteToStart,
+    /// Ctrl-W
+    DeleteWord,
+    /// Ctrl-C
+    Cancel,
+    /// Ctrl-D
+    EndOfInput,
+    Ignored
+}
+
+/// What a key did to the line being edited
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    Editing,
+    Done(String),
+    /// Ctrl-D on an empty line
+    EndOfInput
+}
+
+/// Decodes the next key from the bytes a terminal sends in raw mode, None at the end of the input
+pub fn read_key<R: Read>(input: &mut R) -> io::Result<Option<Key>> {
+    let mut next = || -> io::Result<Option<u8>> {
+        let mut byte = [0];
+        Ok(if input.read(&mut byte)? == 0 { None } else { Some(byte[0]) })
+    };
+
+    let first = match next()? {
+        Some(first) => first,
+        None => return Ok(None)
+    };
+
+    let key = match first {
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08   => Key::Backspace,
+        0x01          => Key::Home,
+        0x05          => Key::End,
+        0x15          => Key::DeleteToStart,
+        0x17          => Key::DeleteWord,
+        0x03          => Key::Cancel,
+        0x04          => Key::EndOfInput,
+        // Escape sequences: ESC [ A and ESC O A like, with ESC [ 3 ~ like ones for delete/home/end
+        0x1b => match next()? {
+            Some(b'[') | Some(b'O') => match next()? {
+                Some(b'A') => Key::Up,
+                Some(b'B') => Key::Down,
+                Some(b'C') => Key::Right,
+                Some(b'D') => Key::Left,
+                Some(b'H') => Key::Home,
+                Some(b'F') => Key::End,
+                Some(digit) if digit.is_ascii_digit() => {
+                    let mut code = vec![digit];
+                    loop {
+                        match next()? {
+                            Some(b'~') | None => break,
+                            Some(byte) => code.push(byte)
+                        }
+                    }
+                    match code.as_slice() {
+                        b"3"        => Key::Delete,
+                        b"1" | b"7" => Key::Home,
+                        b"4" | b"8" => Key::End,
+                        _           => Key::Ignored
+                    }
+                },
+                _ => Key::Ignored
+            },
+            _ => Key::Ignored
+        },
+        byte if byte < 0x20 => Key::Ignored,
+        byte => {
+            // The rest of a UTF-8 character
+            let mut bytes = vec![byte];
+            let length = match byte {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _           => 1
+            };
+            while bytes.len() < length {
+                match next()? {
+                    Some(byte) => bytes.push(byte),
+                    None => break
+                }
+            }
+            match std::str::from_utf8(&bytes).ok().and_then(|text| text.chars().next()) {
+                Some(c) => Key::Char(c),
+                None => Key::Ignored
+            }
+        }
+    };
+
+    Ok(Some(key))
+}
+
+/// The line being edited along with where it is in the history
+#[derive(Debug, Clone, Default)]
+pub struct EditBuffer {
+    line: Vec<char>,
+    cursor: usize,
+    /// Lines from the end of the history, 0 while editing a new line
+    history_offset: usize,
+    /// The new line, kept while going through the history
+    draft: Vec<char>
+}
+
+impl EditBuffer {
+    pub fn new() -> EditBuffer {
+        EditBuffer::default()
+    }
+
+    pub fn line(&self) -> String {
+        self.line.iter().collect()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn apply(&mut self, key: Key, history: &[String]) -> Edit {
+        match key {
+            Key::Char(c) => {
+                self.line.insert(self.cursor, c);
+                self.cursor += 1;
+            },
+            Key::Enter => return Edit::Done(self.line()),
+            Key::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.line.remove(self.cursor);
+            },
+            Key::Delete if self.cursor < self.line.len() => {
+                self.line.remove(self.cursor);
+            },
+            Key::Left if self.cursor > 0 => self.cursor -= 1,
+            Key::Right if self.cursor < self.line.len() => self.cursor += 1,
+            Key::Home => self.cursor = 0,
+            Key::End => self.cursor = self.line.len(),
+            Key::Up if self.history_offset < history.len() => {
+                if self.history_offset == 0 {
+                    self.draft = self.line.clone();
+                }
+                self.history_offset += 1;
+                self.line = history[history.len() - self.history_offset].chars().collect();
+                self.cursor = self.line.len();
+            },
+            Key::Down if self.history_offset > 0 => {
+                self.history_offset -= 1;
+                self.line = if self.history_offset == 0 { self.draft.clone() }
+                            else { history[history.len() - self.history_offset].chars().collect() };
+                self.cursor = self.line.len();
+            },
+            Key::DeleteToStart => {
+                self.line.drain(..self.cursor);
+                self.cursor = 0;
+            },
+            Key::DeleteWord => {
+                // The spaces before the cursor, then the word before them
+                let mut start = self.cursor;
+                while start > 0 && self.line[start - 1].is_whitespace() {
+                    start -= 1;
+                }
+                while start > 0 && !self.line[start - 1].is_whitespace() {
+                    start -= 1;
+                }
+                self.line.drain(start..self.cursor);
+                self.cursor = start;
+            },
+            Key::Cancel => {
+                *self = EditBuffer::new();
+            },
+            Key::EndOfInput if self.line.is_empty() => return Edit::EndOfInput,
+            _ => ()
+        }
+
+        Edit::Editing
+    }
+}
+
+// Puts the terminal back the way it was when dropped
+struct RawMode {
+    settings: String
+}
+
+impl RawMode {
+    fn enable() -> io::Result<RawMode> {
+        let output = Command::new("stty").arg("-g").stdin(Stdio::inherit()).output()?;
+        if !output.status.success() {
+            return Err(io::Error::new(io::ErrorKind::Other, "stty -g failed"));
+        }
+
+        stty(&["-icanon", "-echo", "-isig"])?;
+        Ok(RawMode { settings: String::from_utf8_lossy(&output.stdout).trim().to_string() })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[self.settings.as_str()]);
+    }
+}
+
+fn stty(args: &[&str]) -> io::Result<()> {
+    let status = Command::new("stty").args(args).stdin(Stdio::inherit()).status()?;
+    if !status.success() {
+        return Err(io::Error::new(io::ErrorKind::Other, "stty failed"));
+    }
+    Ok(())
+}
+
+pub struct LineEditor {
+    is_terminal: bool
+}
+
+impl LineEditor {
+    pub fn new() -> LineEditor {
+        use std::io::IsTerminal;
+
+        LineEditor { is_terminal: io::stdin().is_terminal() && io::stdout().is_terminal() }
+    }
+
+    /// Shows the prompt and reads a line, None at the end of the input.
+    /// history is what the up and down keys go through, oldest first.
+    pub fn read_line(&mut self, prompt: &str, history: &[String]) -> io::Result<Option<String>> {
+        let mut stdout = io::stdout();
+        print!("{}", prompt);
+        stdout.flush()?;
+
+        if !self.is_terminal {
+            let mut line = String::new();
+            if io::stdin().lock().read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            return Ok(Some(line.trim_end_matches(&['\r', '\n'][..]).to_string()));
+        }
+
+        let _raw_mode = RawMode::enable()?;
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+        let mut buffer = EditBuffer::new();
+
+        loop {
+            let edit = match read_key(&mut input)? {
+                Some(key) => buffer.apply(key, history),
+                None => Edit::EndOfInput
+            };
+
+            match edit {
+                Edit::Editing => {
+                    // Redraw the whole line, then put the cursor back where it is in the line
+                    let line = buffer.line();
+                    print!("\r{}{}\x1b[K", prompt, line);
+                    let behind_cursor = line.chars().count() - buffer.cursor();
+                    if behind_cursor > 0 {
+                        print!("\x1b[{}D", behind_cursor);
+                    }
+                    stdout.flush()?;
+                },
+                Edit::Done(line) => {
+                    println!();
+                    return Ok(Some(line));
+                },
+                Edit::EndOfInput => {
+                    println!();
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+impl Default for LineEditor {
+    fn default() -> Self {
+        LineEditor::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(bytes: &[u8]) -> Vec<Key> {
+        let mut input = bytes;
+        std::iter::from_fn(|| read_key(&mut input).unwrap()).collect()
+    }
+
+    fn edit(bytes: &[u8], history: &[String]) -> Edit {
+        let mut buffer = EditBuffer::new();
+        keys(bytes).into_iter()
+                   .map(|key| buffer.apply(key, history))
+                   .find(|edit| *edit != Edit::Editing)
+                   .unwrap_or(Edit::Editing)
+    }
+
+    #[test]
+    fn test_read_key() {
+        assert_eq!(keys(b"a\x1b[A\x1b[B\x1b[C\x1b[D\x1bOH\x1b[3~\x7f\r"),
+                   [Key::Char('a'), Key::Up, Key::Down, Key::Right, Key::Left, Key::Home, Key::Delete, Key::Backspace, Key::Enter]);
+        assert_eq!(keys("é".as_bytes()), [Key::Char('é')]);
+        assert_eq!(keys(b"\x1b[15~\x02"), [Key::Ignored, Key::Ignored]);
+    }
+
+    #[test]
+    fn test_editing() {
+        let history = vec![String::from("north"), String::from("take coin")];
+
+        // Inserting in the middle and deleting on both sides of the cursor
+        assert_eq!(edit(b"tke\x1b[D\x1b[Da\x1b[Cx\x7f\r", &history), Edit::Done(String::from("take")));
+        assert_eq!(edit(b"take coin\x01\x1b[3~\x1b[3~\x1b[3~\x1b[3~\x1b[3~\r", &history), Edit::Done(String::from("coin")));
+        assert_eq!(edit(b"take the coin  \x17\x17\r", &history), Edit::Done(String::from("take ")));
+        assert_eq!(edit(b"take coin\x1b[D\x1b[D\x15\r", &history), Edit::Done(String::from("in")));
+
+        // Up goes back in the history, down comes back to the line that was being typed
+        assert_eq!(edit(b"\x1b[A\r", &history), Edit::Done(String::from("take coin")));
+        assert_eq!(edit(b"\x1b[A\x1b[A\x1b[A\r", &history), Edit::Done(String::from("north")));
+        assert_eq!(edit(b"so\x1b[A\x1b[A\x1b[B\x1b[Buth\r", &history), Edit::Done(String::from("south")));
+
+        // Ctrl-C drops the line, Ctrl-D only ends the input on an empty line
+        assert_eq!(edit(b"west\x03east\r", &history), Edit::Done(String::from("east")));
+        assert_eq!(edit(b"west\x04\r", &history), Edit::Done(String::from("west")));
+        assert_eq!(edit(b"\x04", &history), Edit::EndOfInput);
+    }
+}
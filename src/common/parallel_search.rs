@@ -0,0 +1,271 @@
+// Spreads a search over all cores: day 7's phase settings, or exploring a maze with a cloned computer per path.
+//
+// Every thread has its own queue of tasks. It takes its newest task first (depth first, which keeps the number
+// of computers waiting around low) and when it runs out, steals the oldest task from another thread's queue.
+// Visiting a task can queue more tasks, the search ends when every queue is empty and no task is being visited.
+//
+// parallel_map_in_order is the simpler kind of pool, for a fixed list of tasks whose results go out in order,
+// like running every day at once.
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::sync::{mpsc, Condvar, Mutex};
+use std::thread;
+
+thread_local! {
+    // Set on the threads of a pool, so that a search started from one of them doesn't spawn a pool of its own
+    static IN_POOL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Number of threads to use to keep every core busy.
+/// 1 on a thread of a pool, whose siblings already keep the other cores busy.
+pub fn available_threads() -> usize {
+    if IN_POOL.with(|in_pool| in_pool.get()) {
+        return 1;
+    }
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+// What the workers wait on when every queue is empty
+#[derive(Default)]
+struct Progress {
+    // Tasks that are queued or being visited
+    pending: usize,
+    // Bumped whenever tasks are queued or one is done, so that a waiting worker can tell something happened
+    changes: usize,
+    // A visit panicked, the search can't finish
+    aborted: bool
+}
+
+struct Search<T> {
+    queues: Vec<Mutex<VecDeque<T>>>,
+    progress: Mutex<Progress>,
+    changed: Condvar
+}
+
+impl<T> Search<T> {
+    fn update(&self, change: impl FnOnce(&mut Progress)) {
+        let mut progress = self.progress.lock().unwrap();
+        change(&mut progress);
+        progress.changes += 1;
+        self.changed.notify_all();
+    }
+}
+
+// Marks the task being visited as done when dropped, even when the visit panics,
+// so that the other workers don't wait forever for it
+struct Visiting<'a, T> {
+    search: &'a Search<T>
+}
+
+impl<T> Drop for Visiting<'_, T> {
+    fn drop(&mut self) {
+        let panicking = thread::panicking();
+        self.search.update(|progress| {
+            progress.pending -= 1;
+            progress.aborted |= panicking;
+        });
+    }
+}
+
+/// Visits every task, and the tasks they queue, on `threads` threads.
+/// Returns the result with the highest score, None if visiting never gave a result.
+/// A panic in visit stops the search and is passed on to the caller.
+pub fn parallel_search<T, R, K, V, S>(tasks: Vec<T>, threads: usize, visit: V, score: S) -> Option<R>
+    where T: Send,
+          R: Send,
+          K: Ord,
+          V: Fn(T, &mut Vec<T>) -> Option<R> + Sync,
+          S: Fn(&R) -> K + Sync {
+    let threads = threads.max(1);
+    let search = Search {
+        queues: (0..threads).map(|_| Mutex::new(VecDeque::new())).collect(),
+        progress: Mutex::new(Progress { pending: tasks.len(), ..Progress::default() }),
+        changed: Condvar::new()
+    };
+
+    for (i, task) in tasks.into_iter().enumerate() {
+        search.queues[i%threads].lock().unwrap().push_back(task);
+    }
+
+    // No need for more threads than the one we are on
+    if threads == 1 {
+        return search_worker(0, &search, &visit, &score);
+    }
+
+    let results = thread::scope(|scope| {
+        let workers = (0..threads).map(|id| {
+                                      let (search, visit, score) = (&search, &visit, &score);
+                                      scope.spawn(move || {
+                                          IN_POOL.with(|in_pool| in_pool.set(true));
+                                          search_worker(id, search, visit, score)
+                                      })
+                                  })
+                                  .collect::<Vec<_>>();
+
+        workers.into_iter()
+               .map(|worker| worker.join().unwrap())
+               .collect::<Vec<Option<R>>>()
+    });
+
+    results.into_iter()
+           .flatten()
+           .max_by_key(|result| score(result))
+}
+
+fn search_worker<T, R, K, V, S>(id: usize, search: &Search<T>, visit: &V, score: &S) -> Option<R>
+    where K: Ord,
+          V: Fn(T, &mut Vec<T>) -> Option<R>,
+          S: Fn(&R) -> K {
+    let queues = &search.queues;
+    let mut best: Option<R> = None;
+    let mut new_tasks = Vec::new();
+
+    loop {
+        // Taken before looking at the queues, so that tasks queued after an unlucky look still wake us up
+        let changes = {
+            let progress = search.progress.lock().unwrap();
+            if progress.aborted {
+                return best;
+            }
+            progress.changes
+        };
+
+        // Our own queue must be unlocked before locking another one, or two thieves could wait on each other
+        let own_task = queues[id].lock().unwrap().pop_back();
+        let task = own_task.or_else(|| (1..queues.len()).find_map(|offset| queues[(id + offset)%queues.len()].lock()
+                                                                                                          .unwrap()
+                                                                                                          .pop_front()));
+
+        match task {
+            Some(task) => {
+                let visiting = Visiting { search };
+
+                if let Some(result) = visit(task, &mut new_tasks) {
+                    if best.as_ref().map_or(true, |best| score(&result) > score(best)) {
+                        best = Some(result);
+                    }
+                }
+
+                // The new tasks are counted before this one is done, so that pending never drops to 0 too early
+                if !new_tasks.is_empty() {
+                    search.update(|progress| progress.pending += new_tasks.len());
+                    queues[id].lock().unwrap().extend(new_tasks.drain(..));
+                }
+                drop(visiting);
+            },
+            None => {
+                let progress = search.progress.lock().unwrap();
+                // Someone may still be visiting a task, which may queue more
+                let progress = search.changed.wait_while(progress, |progress| progress.pending > 0 && !progress.aborted
+                                                                              && progress.changes == changes)
+                                             .unwrap();
+                if progress.pending == 0 || progress.aborted {
+                    return best;
+                }
+            }
+        }
+    }
+}
+
+/// Does the work of every task on `threads` threads, handing the results to done in the tasks' order,
+/// each one as soon as it and all the ones before it are finished.
+pub fn parallel_map_in_order<T, R, W, D>(tasks: Vec<T>, threads: usize, work: W, mut done: D)
+    where T: Send,
+          R: Send,
+          W: Fn(T) -> R + Sync,
+          D: FnMut(R) {
+    let count = tasks.len();
+    let tasks = Mutex::new(tasks.into_iter().enumerate());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, count.max(1)) {
+            let (tasks, work, sender) = (&tasks, &work, sender.clone());
+            scope.spawn(move || {
+                IN_POOL.with(|in_pool| in_pool.set(true));
+                loop {
+                    // Taken out of the match so that the lock is let go of before working on the task
+                    let task = tasks.lock().unwrap().next();
+                    match task {
+                        Some((i, task)) => sender.send((i, work(task))).unwrap(),
+                        None => return
+                    }
+                }
+            });
+        }
+        // Only the workers' senders are left, so that receiving ends once they are all done
+        drop(sender);
+
+        // The results that came in before some earlier one
+        let mut finished = (0..count).map(|_| None).collect::<Vec<Option<R>>>();
+        let mut next = 0;
+
+        for (i, result) in receiver {
+            finished[i] = Some(result);
+            while let Some(result) = finished.get_mut(next).and_then(|result| result.take()) {
+                done(result);
+                next += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_parallel_search() {
+        let visited = AtomicUsize::new(0);
+
+        // Walks the binary tree of the numbers from 1 to 999, n leading to 2n and 2n + 1
+        let best = parallel_search(vec![1], 4, |n: usize, tasks| {
+                                       visited.fetch_add(1, Ordering::SeqCst);
+                                       tasks.extend([2*n, 2*n + 1].iter().filter(|&&next| next < 1000));
+                                       Some(n)
+                                   },
+                                   |&n| n%100);
+
+        assert_eq!(visited.load(Ordering::SeqCst), 999);
+        assert_eq!(best.map(|n| n%100), Some(99));
+
+        assert_eq!(parallel_search(Vec::new(), 2, |n: usize, _| Some(n), |&n| n), None);
+    }
+
+    #[test]
+    fn test_parallel_search_panic() {
+        // The other workers give up instead of waiting for the task that panicked
+        let search = thread::spawn(|| parallel_search((0..100).collect(), 4, |n: usize, _| {
+                                                          assert!(n != 50, "Task 50 is broken");
+                                                          thread::sleep(std::time::Duration::from_millis(1));
+                                                          Some(n)
+                                                      },
+                                                      |&n| n));
+
+        assert!(search.join().is_err());
+    }
+
+    #[test]
+    fn test_available_threads_in_pool() {
+        let mut threads = Vec::new();
+        parallel_map_in_order(vec![(); 2], 2, |_| available_threads(), |n| threads.push(n));
+
+        assert_eq!(threads, [1, 1]);
+    }
+
+    #[test]
+    fn test_parallel_map_in_order() {
+        let mut results = Vec::new();
+
+        // The first tasks take the longest, so that they finish last
+        parallel_map_in_order((0..8).collect(), 4, |n: u64| {
+                                  thread::sleep(std::time::Duration::from_millis(40 - 5*n));
+                                  n*n
+                              },
+                              |result| results.push(result));
+
+        assert_eq!(results, [0, 1, 4, 9, 16, 25, 36, 49]);
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Where and why some input couldn't be parsed. Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: &str) -> ParseError {
+        ParseError {
+            line,
+            column,
+            message: String::from(message)
+        }
+    }
+
+    /// An error pointing at where token starts in the line, token being a slice of the line
+    pub fn at(line_number: usize, line: &str, token: &str, message: &str) -> ParseError {
+        let line_range = line.as_bytes().as_ptr_range();
+        debug_assert!(line_range.start <= token.as_ptr() && token.as_ptr() <= line_range.end,
+                      "The token {:?} isn't a slice of the line {:?}", token, line);
+        let offset = (token.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+        let column = line.char_indices()
+                         .take_while(|&(i, _)| i < offset)
+                         .count() + 1;
+        ParseError::new(line_number, column, message)
+    }
+}
+
+/// Parses a token of the line, saying what was expected instead when it can't be parsed
+pub fn parse_token<T: FromStr>(line_number: usize, line: &str, token: &str, expected: &str) -> Result<T, ParseError> {
+    token.parse::<T>()
+         .map_err(|_| ParseError::at(line_number, line, token, &format!("Expected {}, got: {}", expected, token)))
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token() {
+        let line = "<x=-1, y=oops, z=2>";
+
+        assert_eq!(parse_token::<isize>(3, line, &line[3..5], "an integer"), Ok(-1));
+        assert_eq!(parse_token::<isize>(3, line, &line[9..13], "an integer"), Err(ParseError::new(3, 10, "Expected an integer, got: oops")));
+        assert_eq!(ParseError::new(3, 10, "Expected an integer").to_string(), "line 3, column 10: Expected an integer");
+    }
+}
@@ -0,0 +1,172 @@
+// Sets up a new day the way all the others are laid out:
+//   src/dayNN.rs                 Solver, process_a/process_b stubs and a test module
+//   testdata/dayNN/input.txt     Empty, for the puzzle input
+//   problem_statements/dayNN.txt A stub to paste the puzzle's description into
+// and adds the day to the days! list in src/lib.rs, which is all it takes for the runner to know about it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn day_source(day: usize) -> String {
+    format!("use super::parse_error::*;
+use super::solution::*;
+
+pub struct Solver;
+
+impl Solution for Solver {{
+    const DAY: usize = {day};
+
+    type Input = Vec<String>;
+    type Params = ();
+    type AnswerA = usize;
+    type AnswerB = usize;
+
+    // TODO: parse the lines into the day's own types, parse_token tells which line and column is malformed
+    fn parse(text: &str) -> Result<Vec<String>, ParseError> {{
+        Ok(text.lines().map(String::from).collect())
+    }}
+
+    fn part_a(input: &Vec<String>, _params: &()) -> Result<usize, String> {{
+        Ok(process_a(input))
+    }}
+
+    fn part_b(input: &Vec<String>, _params: &()) -> Result<usize, String> {{
+        Ok(process_b(input))
+    }}
+}}
+
+pub fn process_a(_lines: &[String]) -> usize {{
+    // TODO
+    0
+}}
+
+pub fn process_b(_lines: &[String]) -> usize {{
+    // TODO
+    0
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    #[test]
+    fn test_a() {{
+        // TODO: the examples from problem_statements/day{day:02}.txt
+        assert_eq!(process_a(&Solver::parse(\"\").unwrap()), 0);
+    }}
+
+    #[test]
+    fn test_b() {{
+        // TODO: the examples from problem_statements/day{day:02}.txt
+        assert_eq!(process_b(&Solver::parse(\"\").unwrap()), 0);
+    }}
+}}
+", day = day)
+}
+
+pub fn problem_statement_stub(day: usize) -> String {
+    format!("--- Day {}: ---\n\nPaste the puzzle's description here, \
+             and annotate its examples with === example === blocks (see common/examples.rs) for tests/examples.rs to run.\n", day)
+}
+
+fn check_day(day: usize) -> Result<(), String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("Advent of Code days go from 1 to 25, got: {}", day));
+    }
+    Ok(())
+}
+
+/// Adds the day to the days! list of lib.rs's text, keeping the list sorted by day, 8 days per line
+pub fn register_day(lib_rs: &str, day: usize) -> Result<String, String> {
+    check_day(day)?;
+
+    let start = lib_rs.find("days!(").ok_or("No days! list in lib.rs")? + "days!(".len();
+    let end = start + lib_rs[start..].find(");").ok_or("Unterminated days! list in lib.rs")?;
+
+    let module = format!("day{:02}", day);
+    let mut modules = lib_rs[start..end].split(|c: char| c == ',' || c.is_whitespace())
+                                        .filter(|item| !item.is_empty())
+                                        .map(String::from)
+                                        .collect::<Vec<String>>();
+
+    if modules.contains(&module) {
+        return Err(format!("{} is already registered", module));
+    }
+
+    modules.push(module);
+    modules.sort_by_key(|module| module.trim_start_matches("day").parse::<usize>().unwrap_or(usize::MAX));
+
+    let list = modules.chunks(8)
+                      .map(|line| line.join(", "))
+                      .collect::<Vec<String>>()
+                      .join(",\n      ");
+
+    Ok(format!("{}{}{}", &lib_rs[..start], list, &lib_rs[end..]))
+}
+
+/// Creates the new day's files under root and registers it, returns the files created or changed
+pub fn create_day(root: &Path, day: usize) -> Result<Vec<PathBuf>, String> {
+    let source = root.join(format!("src/day{:02}.rs", day));
+    let input = root.join(format!("testdata/day{:02}/input.txt", day));
+    let statement = root.join(format!("problem_statements/day{:02}.txt", day));
+    let lib_rs = root.join("src/lib.rs");
+
+    // Check everything first, to not leave a half made day behind
+    check_day(day)?;
+    if let Some(existing) = [&source, &input, &statement].iter().find(|path| path.exists()) {
+        return Err(format!("{} already exists", existing.display()));
+    }
+
+    let lib_text = fs::read_to_string(&lib_rs).map_err(|error| format!("Unable to read {}: {}", lib_rs.display(), error))?;
+    let lib_text = register_day(&lib_text, day)?;
+
+    let write = |path: &Path, contents: &str| -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| format!("Unable to create {}: {}", parent.display(), error))?;
+        }
+        fs::write(path, contents).map_err(|error| format!("Unable to write {}: {}", path.display(), error))
+    };
+
+    write(&source, &day_source(day))?;
+    write(&input, "")?;
+    write(&statement, &problem_statement_stub(day))?;
+    write(&lib_rs, &lib_text)?;
+
+    Ok(vec![source, input, statement, lib_rs])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_day() {
+        let lib_rs = "pub mod json;\n\ndays!(day01, day02, day03, day04, day05, day06, day07, day08,\n      day09, day10);\n";
+
+        assert_eq!(register_day(lib_rs, 17),
+                   Ok(String::from("pub mod json;\n\ndays!(day01, day02, day03, day04, day05, day06, day07, day08,\n      day09, day10, day17);\n")));
+        assert_eq!(register_day(lib_rs, 9), Err(String::from("day09 is already registered")));
+        assert_eq!(register_day("pub mod json;", 1), Err(String::from("No days! list in lib.rs")));
+        assert_eq!(register_day(lib_rs, 0), Err(String::from("Advent of Code days go from 1 to 25, got: 0")));
+        assert_eq!(register_day(lib_rs, 100), Err(String::from("Advent of Code days go from 1 to 25, got: 100")));
+
+        // By day rather than by name, a day100 module stays after day11
+        assert_eq!(register_day("days!(day09, day100);", 11), Ok(String::from("days!(day09, day11, day100);")));
+    }
+
+    #[test]
+    fn test_create_day() {
+        let root = std::env::temp_dir().join(format!("scaffold_test_{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "days!(day01);\n").unwrap();
+
+        assert_eq!(create_day(&root, 2).map(|files| files.len()), Ok(4));
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), "days!(day01, day02);\n");
+        assert!(fs::read_to_string(root.join("src/day02.rs")).unwrap().contains("const DAY: usize = 2;"));
+        assert!(create_day(&root, 2).is_err());
+        assert_eq!(create_day(&root, 26), Err(String::from("Advent of Code days go from 1 to 25, got: 26")));
+        assert!(!root.join("src/day26.rs").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,249 @@
+// Every day implements Solution, and the days! macro in lib.rs puts them all in the registry that the binary runs.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use super::parse_error::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    A,
+    B
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::A => write!(f, "a"),
+            Part::B => write!(f, "b")
+        }
+    }
+}
+
+/// What a day needs besides its input, like day 8's image size.
+/// The defaults are the values for our own puzzle inputs, set overrides them from "key=value" text.
+pub trait Params: Default {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String>;
+}
+
+/// For the days that need nothing but their input
+impl Params for () {
+    fn set(&mut self, key: &str, _value: &str) -> Result<(), String> {
+        Err(format!("Unknown parameter: {}", key))
+    }
+}
+
+/// Parses a parameter's value, with an error message naming the parameter
+pub fn parse_param<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.trim()
+         .parse::<T>()
+         .map_err(|_| format!("Invalid value for {}: {}", key, value))
+}
+
+/// Tells inputs apart, to know which answers go with which input. 64 bit FNV-1a, in hex.
+pub fn input_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    format!("{:016x}", hash)
+}
+
+pub trait Solution {
+    const DAY: usize;
+
+    type Input;
+    type Params: Params;
+    type AnswerA: fmt::Display;
+    type AnswerB: fmt::Display;
+
+    /// Our puzzle input, relative to the crate's root
+    fn input_path() -> String {
+        format!("testdata/day{:02}/input.txt", Self::DAY)
+    }
+
+    /// Parses the puzzle input into the day's own types, the error says where the input is malformed
+    fn parse(text: &str) -> Result<Self::Input, ParseError>;
+    /// The error says why the part has no answer for an input that parsed fine, like a program that never gives one
+    fn part_a(input: &Self::Input, params: &Self::Params) -> Result<Self::AnswerA, String>;
+    fn part_b(input: &Self::Input, params: &Self::Params) -> Result<Self::AnswerB, String>;
+}
+
+/// Why a day couldn't give an answer
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunError {
+    /// A parameter the day doesn't have, or a value it can't use
+    Param(String),
+    Parse(ParseError),
+    Solve(String)
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunError::Param(message) => write!(f, "{}", message),
+            RunError::Parse(error) => write!(f, "Invalid input, {}", error),
+            RunError::Solve(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+/// An answer along with how long it took to parse the input and to solve the part
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedAnswer {
+    pub answer: String,
+    pub parse: Duration,
+    pub solve: Duration
+}
+
+/// The same as Solution, minus the types that differ from day to day, so that all of them fit in a single list
+pub trait Runner: Send + Sync {
+    fn day(&self) -> usize;
+    fn input_path(&self) -> String;
+    fn run_timed(&self, text: &str, part: Part, params: &[(String, String)]) -> Result<TimedAnswer, RunError>;
+
+    fn run(&self, text: &str, part: Part, params: &[(String, String)]) -> Result<String, RunError> {
+        self.run_timed(text, part, params).map(|timed| timed.answer)
+    }
+}
+
+// fn() -> S so that the runner is Send and Sync whatever S is
+struct SolutionRunner<S: Solution>(PhantomData<fn() -> S>);
+
+impl<S: Solution> Runner for SolutionRunner<S> {
+    fn day(&self) -> usize {
+        S::DAY
+    }
+
+    fn input_path(&self) -> String {
+        S::input_path()
+    }
+
+    fn run_timed(&self, text: &str, part: Part, params: &[(String, String)]) -> Result<TimedAnswer, RunError> {
+        let mut day_params = S::Params::default();
+        for (key, value) in params.iter() {
+            day_params.set(key, value).map_err(RunError::Param)?;
+        }
+
+        let start = Instant::now();
+        let input = S::parse(text).map_err(RunError::Parse)?;
+        let parse = start.elapsed();
+
+        // Formatting the answer is not part of solving it
+        let start = Instant::now();
+        let answer: Box<dyn fmt::Display> = match part {
+            Part::A => Box::new(S::part_a(&input, &day_params).map_err(RunError::Solve)?),
+            Part::B => Box::new(S::part_b(&input, &day_params).map_err(RunError::Solve)?)
+        };
+        let solve = start.elapsed();
+
+        Ok(TimedAnswer { answer: answer.to_string(), parse, solve })
+    }
+}
+
+pub fn runner<S: Solution + 'static>() -> Box<dyn Runner> {
+    Box::new(SolutionRunner::<S>(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    #[derive(Default)]
+    struct Scale {
+        factor: isize
+    }
+
+    impl Params for Scale {
+        fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+            match key {
+                "factor" => self.factor = parse_param(key, value)?,
+                _ => return Err(format!("Unknown parameter: {}", key))
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Solution for Sum {
+        const DAY: usize = 1;
+
+        type Input = Vec<isize>;
+        type Params = Scale;
+        type AnswerA = isize;
+        type AnswerB = isize;
+
+        fn parse(text: &str) -> Result<Vec<isize>, ParseError> {
+            text.split(',').map(|item| parse_token(1, text, item, "an integer")).collect()
+        }
+
+        fn part_a(input: &Vec<isize>, _params: &Scale) -> Result<isize, String> {
+            Ok(input.iter().sum())
+        }
+
+        fn part_b(input: &Vec<isize>, params: &Scale) -> Result<isize, String> {
+            input.iter()
+                 .sum::<isize>()
+                 .checked_mul(params.factor)
+                 .ok_or_else(|| String::from("The sum overflows once scaled"))
+        }
+    }
+
+    // Parsing takes a while and solving next to nothing, for bench's timings to tell which is which
+    struct SlowParse;
+
+    impl Solution for SlowParse {
+        const DAY: usize = 2;
+
+        type Input = ();
+        type Params = ();
+        type AnswerA = usize;
+        type AnswerB = usize;
+
+        fn parse(_text: &str) -> Result<(), ParseError> {
+            std::thread::sleep(Duration::from_millis(20));
+            Ok(())
+        }
+
+        fn part_a(_input: &(), _params: &()) -> Result<usize, String> {
+            Ok(1)
+        }
+
+        fn part_b(_input: &(), _params: &()) -> Result<usize, String> {
+            Ok(2)
+        }
+    }
+
+    #[test]
+    fn test_runner() {
+        let runner = runner::<Sum>();
+        let params = |key: &str, value: &str| vec![(String::from(key), String::from(value))];
+
+        assert_eq!(runner.day(), 1);
+        assert_eq!(runner.input_path(), "testdata/day01/input.txt");
+        assert_eq!(runner.run("1,2,3", Part::A, &[]), Ok(String::from("6")));
+        assert_eq!(runner.run("1,2,3", Part::B, &params("factor", "10")), Ok(String::from("60")));
+        assert_eq!(runner.run("1,2,3", Part::B, &params("factor", "ten")), Err(RunError::Param(String::from("Invalid value for factor: ten"))));
+        assert_eq!(runner.run("1,2,3", Part::B, &params("offset", "1")), Err(RunError::Param(String::from("Unknown parameter: offset"))));
+        assert_eq!(runner.run("1,x,3", Part::A, &[]).map_err(|error| error.to_string()),
+                   Err(String::from("Invalid input, line 1, column 3: Expected an integer, got: x")));
+        assert_eq!(runner.run("1,2,3", Part::B, &params("factor", &isize::MAX.to_string())),
+                   Err(RunError::Solve(String::from("The sum overflows once scaled"))));
+    }
+
+    #[test]
+    fn test_run_timed() {
+        let timed = runner::<SlowParse>().run_timed("", Part::B, &[]).unwrap();
+
+        assert_eq!(timed.answer, "2");
+        assert!(timed.parse >= Duration::from_millis(20));
+        assert!(timed.solve < timed.parse);
+    }
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+        assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+    }
+}
@@ -0,0 +1,90 @@
+// Notices when files change by polling their modification times, which needs nothing from the OS beyond stat.
+// Directories are watched along with everything under them, so that new files are noticed too.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    seen: HashMap<PathBuf, SystemTime>
+}
+
+impl Watcher {
+    /// Starts from the files as they are now, only later changes are reported
+    pub fn new(paths: Vec<PathBuf>) -> Watcher {
+        let mut watcher = Watcher { paths, seen: HashMap::new() };
+        watcher.seen = watcher.scan();
+        watcher
+    }
+
+    fn scan(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut files = HashMap::new();
+        for path in self.paths.iter() {
+            scan_path(path, &mut files);
+        }
+        files
+    }
+
+    /// The files created, modified or removed since the last call, sorted
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let files = self.scan();
+
+        let mut changed = files.iter()
+                               .filter(|&(path, modified)| self.seen.get(path) != Some(modified))
+                               .map(|(path, _)| path.clone())
+                               .chain(self.seen.keys().filter(|path| !files.contains_key(*path)).cloned())
+                               .collect::<Vec<PathBuf>>();
+        changed.sort();
+
+        self.seen = files;
+        changed
+    }
+}
+
+// Missing or unreadable paths are left out, they show up as changed once they can be read
+fn scan_path(path: &Path, files: &mut HashMap<PathBuf, SystemTime>) {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return
+    };
+
+    if metadata.is_dir() {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                scan_path(&entry.path(), files);
+            }
+        }
+    } else if let Ok(modified) = metadata.modified() {
+        files.insert(path.to_path_buf(), modified);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_watcher() {
+        let root = std::env::temp_dir().join(format!("watch_test_{}", std::process::id()));
+        fs::create_dir_all(root.join("day01")).unwrap();
+        fs::write(root.join("day01/input.txt"), "12").unwrap();
+
+        let mut watcher = Watcher::new(vec![root.join("day01"), root.join("day01.rs")]);
+        assert!(watcher.changed().is_empty());
+
+        // Set explicitly, as a file system may only keep the time to the second
+        let input = fs::OpenOptions::new().write(true).open(root.join("day01/input.txt")).unwrap();
+        input.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+        fs::write(root.join("day01.rs"), "").unwrap();
+        assert_eq!(watcher.changed(), [root.join("day01/input.txt"), root.join("day01.rs")]);
+        assert!(watcher.changed().is_empty());
+
+        fs::remove_file(root.join("day01.rs")).unwrap();
+        assert_eq!(watcher.changed(), [root.join("day01.rs")]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,18 +1,63 @@
-pub fn process_a(text: &str) -> u64 {
+use super::parse_error::*;
+use super::solution::*;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 1;
+
+    type Input = Vec<u64>;
+    type Params = ();
+    type AnswerA = u64;
+    type AnswerB = u64;
+
+    fn parse(text: &str) -> Result<Vec<u64>, ParseError> {
+        masses(text)
+    }
+
+    fn part_a(input: &Vec<u64>, _params: &()) -> Result<u64, String> {
+        process_a(input)
+    }
+
+    fn part_b(input: &Vec<u64>, _params: &()) -> Result<u64, String> {
+        process_b(input)
+    }
+}
+
+// Lighter modules would need negative fuel
+const MINIMUM_MASS: u64 = 6;
+
+// One module's mass per line
+fn masses(text: &str) -> Result<Vec<u64>, ParseError> {
     text.lines()
-        .filter(|line| line.len() > 0)
-        .fold(0, |sum, line| sum + (line.parse::<u64>().expect("Expected an integer"))/3 - 2)
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let mass = parse_token(i + 1, line, line.trim(), "a mass")?;
+            if mass < MINIMUM_MASS {
+                return Err(ParseError::at(i + 1, line, line.trim(), &format!("Expected a mass of at least {}, got: {}", MINIMUM_MASS, mass)));
+            }
+            Ok(mass)
+        })
+        .collect()
+}
+
+fn total(mut fuels: impl Iterator<Item = u64>) -> Result<u64, String> {
+    fuels.try_fold(0u64, |sum, fuel| sum.checked_add(fuel))
+         .ok_or_else(|| String::from("The total fuel doesn't fit in 64 bits"))
 }
 
-pub fn process_b(text: &str) -> i64 {
-    fn fuel_required(weight: i64, total: i64) -> i64 {
-        let current_fuel_required = weight/3 - 2;
-        return if current_fuel_required <= 0 { total } else { fuel_required(current_fuel_required, total + current_fuel_required) }
+pub fn process_a(masses: &[u64]) -> Result<u64, String> {
+    total(masses.iter().map(|mass| mass/3 - 2))
+}
+
+pub fn process_b(masses: &[u64]) -> Result<u64, String> {
+    fn fuel_required(weight: u64, total: u64) -> u64 {
+        // Anything below 9 needs no fuel of its own (or rather, negative fuel)
+        if weight < 9 { total } else { fuel_required(weight/3 - 2, total + weight/3 - 2) }
     }
 
-    text.lines()
-        .filter(|line| line.len() > 0)
-        .fold(0, |sum, line| sum + fuel_required(line.parse::<i64>().expect("Expected an integer"), 0))
+    total(masses.iter().map(|&mass| fuel_required(mass, 0)))
 }
 
 #[cfg(test)]
@@ -21,16 +66,16 @@ mod tests {
     #[test]
     fn test_a() {
         // For a mass of 12, divide by 3 and round down to get 4, then subtract 2 to get 2.
-        assert_eq!(process_a("12"), 2);
+        assert_eq!(process_a(&masses("12").unwrap()), Ok(2));
 
         // For a mass of 14, dividing by 3 and rounding down still yields 4, so the fuel required is also 2.
-        assert_eq!(process_a("14"), 2);
+        assert_eq!(process_a(&masses("14").unwrap()), Ok(2));
 
         // For a mass of 1969, the fuel required is 654.
-        assert_eq!(process_a("1969"), 654);
+        assert_eq!(process_a(&masses("1969").unwrap()), Ok(654));
 
         // For a mass of 100756, the fuel required is 33583.
-        assert_eq!(process_a("100756"), 33583);
+        assert_eq!(process_a(&masses("100756").unwrap()), Ok(33583));
     }
 
     #[test]
@@ -38,14 +83,18 @@ mod tests {
         // A module of mass 14 requires 2 fuel. This fuel requires no further fuel
         // (2 divided by 3 and rounded down is 0, which would call for a negative fuel),
         // so the total fuel required is still just 2.
-        assert_eq!(process_b("14"), 2);
+        assert_eq!(process_b(&masses("14").unwrap()), Ok(2));
 
         // At first, a module of mass 1969 requires 654 fuel. Then, this fuel requires 216 more fuel (654 / 3 - 2).
         // 216 then requires 70 more fuel, which requires 21 fuel, which requires 5 fuel, which requires no further fuel.
         // So, the total fuel required for a module of mass 1969 is 654 + 216 + 70 + 21 + 5 = 966.
-        assert_eq!(process_b("1969"), 966);
+        assert_eq!(process_b(&masses("1969").unwrap()), Ok(966));
 
         //The fuel required by a module of mass 100756 and its fuel is: 33583 + 11192 + 3728 + 1240 + 411 + 135 + 43 + 12 + 2 = 50346.
-        assert_eq!(process_b("100756"), 50346);
+        assert_eq!(process_b(&masses("100756").unwrap()), Ok(50346));
+
+        assert_eq!(masses("14\n1969\n19x69"), Err(ParseError::new(3, 1, "Expected a mass, got: 19x69")));
+        assert_eq!(masses("14\n1"), Err(ParseError::new(2, 1, "Expected a mass of at least 6, got: 1")));
+        assert_eq!(process_a(&[u64::MAX; 4]), Err(String::from("The total fuel doesn't fit in 64 bits")));
     }
 }
\ No newline at end of file
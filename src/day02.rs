@@ -1,73 +1,102 @@
-pub fn process_a(text: &str) -> usize {
-    //Convert the text input to a vector of integers
-    let mut memory = to_vector_of_usize(text);
+use super::parse_error::*;
+use super::solution::*;
 
-    if memory.len() < 2 {
-        println!("Empty memory cannot be processed!");
-        return 0;
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 2;
+
+    type Input = Vec<usize>;
+    type Params = ();
+    type AnswerA = usize;
+    type AnswerB = usize;
+
+    fn parse(text: &str) -> Result<Vec<usize>, ParseError> {
+        to_vector_of_usize(text)
     }
 
-    memory[1] = 12;
-    memory[2] = 2;
-    process(&mut memory)
+    fn part_a(input: &Vec<usize>, _params: &()) -> Result<usize, String> {
+        process_a(input)
+    }
+
+    fn part_b(input: &Vec<usize>, _params: &()) -> Result<usize, String> {
+        process_b(input)
+    }
 }
 
-pub fn process_b(text: &str) -> usize {
-    let memory = to_vector_of_usize(text);
+pub fn process_a(program: &[usize]) -> Result<usize, String> {
+    run_with(program, 12, 2)
+}
 
-    if memory.len() < 2 {
-        println!("Empty memory cannot be processed!");
-        return 0;
-    }
+pub fn process_b(program: &[usize]) -> Result<usize, String> {
+    // Checked before the search, so that a program too short for the noun and verb isn't "not found"
+    run_with(program, 0, 0)?;
 
     for i in 0..100 {
         for j in 0..100 {
-            let mut new_memory = memory.clone();
-            new_memory[1] = i;
-            new_memory[2] = j;
-
-            if process(&mut new_memory) == 19690720 {
-                return 100*i + j;
+            // A noun and verb that make the program fail are just not the ones we are looking for
+            if run_with(program, i, j) == Ok(19690720) {
+                return Ok(100*i + j);
             }
         }
     }
 
-    println!("Unable to find a valid pair of inputs that produce: 19690720");
-    return 0;
+    Err(String::from("Unable to find a valid pair of inputs that produce: 19690720"))
+}
+
+// Runs the program with the noun and verb at addresses 1 and 2
+fn run_with(program: &[usize], noun: usize, verb: usize) -> Result<usize, String> {
+    if program.len() < 3 {
+        return Err(format!("Expected at least 3 values to put the noun and verb in, got: {}", program.len()));
+    }
+
+    let mut memory = program.to_vec();
+    memory[1] = noun;
+    memory[2] = verb;
+    process(&mut memory)
 }
 
-fn to_vector_of_usize(text: &str) -> Vec<usize> {
-    text.split(',')
-        .filter(|item| item.len() > 0)
-        .map(|item| item.parse::<usize>().unwrap())
-        .collect::<Vec<usize>>()
+// The program, its values separated by commas
+fn to_vector_of_usize(text: &str) -> Result<Vec<usize>, ParseError> {
+    text.lines()
+        .enumerate()
+        .flat_map(|(i, line)| line.split(',')
+                                  .map(|item| item.trim())
+                                  .filter(|item| !item.is_empty())
+                                  .map(move |item| parse_token(i + 1, line, item, "a non negative integer")))
+        .collect()
 }
 
-fn process(memory: &mut Vec<usize>) -> usize {
+fn process(memory: &mut [usize]) -> Result<usize, String> {
     let mut p = 0;
 
-    while p + 3 < memory.len() {
-        let operation = memory[p];
-        let result_location = memory[p+3];
-
-        match operation {
-            1 => memory[result_location] = memory[memory[p+1]] + memory[memory[p+2]],
-            2 => memory[result_location] = memory[memory[p+1]] * memory[memory[p+2]],
-            _ => {
-                if operation != 99 {
-                    println!("Invalid operation!: {} ", operation);
-                }
-                break
-            }
-        };
+    loop {
+        let operation = *memory.get(p).ok_or_else(|| format!("The program ran off the end of memory at {} without halting", p))?;
+        if operation == 99 {
+            return Ok(memory[0]);
+        }
+
+        // The parameters, and the values at the addresses they point to
+        let parameter = |i: usize| memory.get(p + i)
+                                        .copied()
+                                        .ok_or_else(|| format!("The instruction at {} is missing parameter {}", p, i));
+        let value = |i: usize| parameter(i).and_then(|address| memory.get(address)
+                                                                  .copied()
+                                                                  .ok_or_else(|| format!("The instruction at {} reads past the end of memory, at {}", p, address)));
+
+        let result = match operation {
+            1 => value(1)?.checked_add(value(2)?),
+            2 => value(1)?.checked_mul(value(2)?),
+            _ => return Err(format!("Invalid operation {} at {}", operation, p))
+        }.ok_or_else(|| format!("The instruction at {} overflows", p))?;
+
+        let result_location = parameter(3)?;
+        *memory.get_mut(result_location).ok_or_else(|| format!("The instruction at {} writes past the end of memory, at {}", p, result_location))? = result;
 
         p += 4;
     }
-
-    memory[0]
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,12 +104,21 @@ mod tests {
     #[test]
     fn test_a() {
         // 1,0,0,0,99 becomes 2,0,0,0,99 (1 + 1 = 2).
-        assert_eq!(process(&mut(vec![1,0,0,0,99])), 2);
+        assert_eq!(process(&mut([1,0,0,0,99])), Ok(2));
         // 2,3,0,3,99 becomes 2,3,0,6,99 (3 * 2 = 6).
-        assert_eq!(process(&mut(vec![2,3,0,3,99])), 2);
+        assert_eq!(process(&mut([2,3,0,3,99])), Ok(2));
         // 2,4,4,5,99,0 becomes 2,4,4,5,99,9801 (99 * 99 = 9801).
-        assert_eq!(process(&mut(vec![2,4,4,5,99,0])), 2);
+        assert_eq!(process(&mut([2,4,4,5,99,0])), Ok(2));
         // 1,1,1,4,99,5,6,0,99 becomes 30,1,1,4,2,5,6,0,99.
-        assert_eq!(process(&mut(vec![1,1,1,4,99,5,6,0,99])), 30);
+        assert_eq!(process(&mut([1,1,1,4,99,5,6,0,99])), Ok(30));
+
+        assert_eq!(process_a(&[1,2]), Err(String::from("Expected at least 3 values to put the noun and verb in, got: 2")));
+        assert_eq!(process(&mut([1,0,9,0,99])), Err(String::from("The instruction at 0 reads past the end of memory, at 9")));
+        assert_eq!(process(&mut([1,0,0])), Err(String::from("The instruction at 0 is missing parameter 3")));
+        assert_eq!(process(&mut([1,0,0,0])), Err(String::from("The program ran off the end of memory at 4 without halting")));
+        assert_eq!(process(&mut([7,0,0,0,99])), Err(String::from("Invalid operation 7 at 0")));
+        assert_eq!(process_b(&[99,0,0]), Err(String::from("Unable to find a valid pair of inputs that produce: 19690720")));
+
+        assert_eq!(to_vector_of_usize("1,0,0,3,\n99,-1"), Err(ParseError::new(2, 4, "Expected a non negative integer, got: -1")));
     }
 }
\ No newline at end of file
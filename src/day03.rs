@@ -1,13 +1,32 @@
-type Point = (isize, isize);
+use super::parse_error::*;
+use super::solution::*;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 3;
 
-pub fn process_a(text: &str) -> isize {
-    let wires = wire_points(text);
+    type Input = Vec<Vec<Point>>;
+    type Params = ();
+    type AnswerA = isize;
+    type AnswerB = isize;
 
-    if wires.len() != 2 || wires[0].len() < 3 || wires[1].len() < 3 {
-        println!("Invalid Input! {}", text);
-        return 0;
+    fn parse(text: &str) -> Result<Vec<Vec<Point>>, ParseError> {
+        wire_points(text)
     }
 
+    fn part_a(input: &Vec<Vec<Point>>, _params: &()) -> Result<isize, String> {
+        process_a(input)
+    }
+
+    fn part_b(input: &Vec<Vec<Point>>, _params: &()) -> Result<isize, String> {
+        process_b(input)
+    }
+}
+
+type Point = (isize, isize);
+
+pub fn process_a(wires: &[Vec<Point>]) -> Result<isize, String> {
     let left_wire = &wires[0];
     let right_wire = &wires[1];
 
@@ -28,18 +47,10 @@ pub fn process_a(text: &str) -> isize {
     }
 
     intersections.sort();
-    // Blindly assume there is an intersection other than 0
-    intersections[1].0
+    closest_crossing(&intersections)
 }
 
-pub fn process_b(text: &str) -> isize {
-    let wires = wire_points(text);
-
-    if wires.len() != 2 || wires[0].len() < 3 || wires[1].len() < 3 {
-        println!("Invalid Input! {}", text);
-        return 0;
-    }
-
+pub fn process_b(wires: &[Vec<Point>]) -> Result<isize, String> {
     let left_wire = &wires[0];
     let right_wire = &wires[1];
 
@@ -69,8 +80,14 @@ pub fn process_b(text: &str) -> isize {
     }
 
     intersections.sort();
-    // Blindly assume there is an intersection other than 0
-    intersections[1].0
+    closest_crossing(&intersections)
+}
+
+// The first of the sorted intersections is the central port, where both wires start
+fn closest_crossing(intersections: &[(isize, Point)]) -> Result<isize, String> {
+    intersections.get(1)
+                 .map(|crossing| crossing.0)
+                 .ok_or_else(|| String::from("The wires never cross besides at the central port"))
 }
 
 fn intersection(l1: Point, l2: Point, r1: Point, r2: Point) -> Option<Point> {
@@ -86,38 +103,40 @@ fn intersection(l1: Point, l2: Point, r1: Point, r2: Point) -> Option<Point> {
     }
 }
 
-fn wire_points (text: &str) -> Vec<Vec<Point>> {
-    let wire_commands = text.lines()
-                            .filter(|&line| line.len() > 0)
-                            .map(|line| line.split(',')
-                                            .map(|item| String::from(item.trim()))
-                                            .filter(|item| item.len() > 0)
-                                            .map(|item| {
-                                                let value = (item[1..]).parse::<isize>().unwrap();
-
-                                                match item.chars().next().unwrap() {
-                                                'R' => (value, 0),
-                                                'L' => (-value, 0),
-                                                'U' => (0, value),
-                                                'D' => (0, -value),
-                                                _   => (0, 0)
-                                            }}).collect::<Vec<Point>>())
-                            .collect::<Vec<Vec<Point>>>();
-
-    wire_commands.iter().map(|commands| {
-                                            let mut result = vec![(0, 0)];
-                                            let mut current = (0, 0);
-
-                                            for command in commands.iter() {
-                                                let p = (current.0 + command.0, current.1 + command.1);
-                                                result.push(p);
-                                                current = p;
-                                            }
-
-                                            result
-                                        })
-                        .collect::<Vec<Vec<Point>>>()
-            
+// The corners of each wire's path, starting from the central port
+fn wire_points(text: &str) -> Result<Vec<Vec<Point>>, ParseError> {
+    let mut wires = Vec::new();
+
+    for (line_index, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let line_number = line_index + 1;
+        let mut current = (0, 0);
+        let mut points = vec![current];
+
+        for item in line.split(',').map(|item| item.trim()).filter(|item| !item.is_empty()) {
+            let direction = match item.chars().next() {
+                Some('R') => (1, 0),
+                Some('L') => (-1, 0),
+                Some('U') => (0, 1),
+                Some('D') => (0, -1),
+                _ => return Err(ParseError::at(line_number, line, item, &format!("Expected a move like R8, got: {}", item)))
+            };
+            let distance = parse_token::<isize>(line_number, line, &item[1..], "a distance")?;
+
+            current = (current.0 + direction.0*distance, current.1 + direction.1*distance);
+            points.push(current);
+        }
+
+        if points.len() < 3 {
+            return Err(ParseError::new(line_number, 1, "Expected a wire of at least two moves"));
+        }
+        wires.push(points);
+    }
+
+    if wires.len() != 2 {
+        return Err(ParseError::new(1, 1, &format!("Expected two wires, one per line, got: {}", wires.len())));
+    }
+
+    Ok(wires)
 }
 
 #[cfg(test)]
@@ -126,21 +145,25 @@ mod tests {
 
     #[test]
     fn test_a() {
-        assert_eq!(process_a("R8,U5,L5,D3\n
-        U7,R6,D4,L4"), 6);
-        assert_eq!(process_a("R75,D30,R83,U83,L12,D49,R71,U7,L72\n
-        U62,R66,U55,R34,D71,R55,D58,R83"), 159);
-        assert_eq!(process_a("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\n
-        U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"), 135);
+        assert_eq!(process_a(&wire_points("R8,U5,L5,D3\n
+        U7,R6,D4,L4").unwrap()), Ok(6));
+        assert_eq!(process_a(&wire_points("R75,D30,R83,U83,L12,D49,R71,U7,L72\n
+        U62,R66,U55,R34,D71,R55,D58,R83").unwrap()), Ok(159));
+        assert_eq!(process_a(&wire_points("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\n
+        U98,R91,D20,R16,D67,R40,U7,R15,U6,R7").unwrap()), Ok(135));
+
+        assert_eq!(wire_points("R8,U5,L5,D3\nU7,R6,X4,L4"), Err(ParseError::new(2, 7, "Expected a move like R8, got: X4")));
+        assert_eq!(wire_points("R8,U5,L5,D3\nU7,R6,D4,Lx"), Err(ParseError::new(2, 11, "Expected a distance, got: x")));
+        assert_eq!(wire_points("R8,U5,L5,D3"), Err(ParseError::new(1, 1, "Expected two wires, one per line, got: 1")));
     }
 
     #[test]
     fn test_b() {
-        assert_eq!(process_b("R8,U5,L5,D3\n
-        U7,R6,D4,L4"), 30);
-        assert_eq!(process_b("R75,D30,R83,U83,L12,D49,R71,U7,L72\n
-        U62,R66,U55,R34,D71,R55,D58,R83"), 610);
-        assert_eq!(process_b("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\n
-        U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"), 410);
+        assert_eq!(process_b(&wire_points("R8,U5,L5,D3\n
+        U7,R6,D4,L4").unwrap()), Ok(30));
+        assert_eq!(process_b(&wire_points("R75,D30,R83,U83,L12,D49,R71,U7,L72\n
+        U62,R66,U55,R34,D71,R55,D58,R83").unwrap()), Ok(610));
+        assert_eq!(process_b(&wire_points("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\n
+        U98,R91,D20,R16,D67,R40,U7,R15,U6,R7").unwrap()), Ok(410));
     }
 }
\ No newline at end of file
@@ -1,13 +1,45 @@
-pub fn process_a(lower: usize, higher:usize) -> usize {
-    (lower..higher).filter(|&v| is_valid_password_a(v))
-                   .collect::<Vec<usize>>()
-                   .len()
+use super::parse_error::*;
+use super::solution::*;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 4;
+
+    type Input = (usize, usize);
+    type Params = ();
+    type AnswerA = usize;
+    type AnswerB = usize;
+
+    fn parse(text: &str) -> Result<(usize, usize), ParseError> {
+        // The range is given as lower-higher
+        let line = text.lines().next().unwrap_or("");
+        let mut bounds = line.splitn(2, '-');
+
+        match (bounds.next(), bounds.next()) {
+            (Some(lower), Some(higher)) => Ok((parse_token(1, line, lower.trim(), "the lower bound")?,
+                                               parse_token(1, line, higher.trim(), "the higher bound")?)),
+            _ => Err(ParseError::new(1, 1, "Expected a range like 136818-685979"))
+        }
+    }
+
+    fn part_a(input: &(usize, usize), _params: &()) -> Result<usize, String> {
+        process_a(input.0, input.1)
+    }
+
+    fn part_b(input: &(usize, usize), _params: &()) -> Result<usize, String> {
+        process_b(input.0, input.1)
+    }
 }
 
-pub fn process_b(lower: usize, higher:usize) -> usize {
-    (lower..higher).filter(|&v| is_valid_password_b(v))
-                   .collect::<Vec<usize>>()
-                   .len()
+pub fn process_a(lower: usize, higher:usize) -> Result<usize, String> {
+    Ok((lower..higher).filter(|&v| is_valid_password_a(v))
+                      .count())
+}
+
+pub fn process_b(lower: usize, higher:usize) -> Result<usize, String> {
+    Ok((lower..higher).filter(|&v| is_valid_password_b(v))
+                      .count())
 }
 
 fn six_digits(mut value: usize) -> [usize; 6] {
@@ -68,6 +100,10 @@ mod tests {
         assert_eq!(is_valid_password_a(223450), false);
         // 123789 does not meet these criteria (no double).
         assert_eq!(is_valid_password_a(123789), false);
+
+        assert_eq!(Solver::parse("136818-685979"), Ok((136818, 685979)));
+        assert_eq!(Solver::parse("136818 to 685979"), Err(ParseError::new(1, 1, "Expected a range like 136818-685979")));
+        assert_eq!(Solver::parse("136818-68597x"), Err(ParseError::new(1, 8, "Expected the higher bound, got: 68597x")));
     }
 
     #[test]
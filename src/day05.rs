@@ -1,24 +1,62 @@
 use super::intcode_computer::*;
+use super::intcode_loader::*;
+use super::parse_error::*;
+use super::solution::*;
 
-pub fn process_a(text: &str) -> isize {
-    let mut computer = IntCodeComputer::from(text);
-    computer.push_input(1);
-    computer.process(ReturnEvent::HaltEvent);
+pub struct Solver;
 
-    let output =  computer.pop_all_output();
-    assert!(output.len() > 0);
-    // Make sure all outputs except the last one are 0
-    assert!(&output[..output.len() - 1].iter().all(|&value| value == 0));
-    *output.last().unwrap()
+impl Solution for Solver {
+    const DAY: usize = 5;
+
+    type Input = Program;
+    type Params = ();
+    type AnswerA = isize;
+    type AnswerB = isize;
+
+    fn parse(text: &str) -> Result<Program, ParseError> {
+        parse_program(text)
+    }
+
+    fn part_a(input: &Program, _params: &()) -> Result<isize, String> {
+        process_a(input)
+    }
+
+    fn part_b(input: &Program, _params: &()) -> Result<isize, String> {
+        process_b(input)
+    }
+}
+
+pub fn process_a(program: &Program) -> Result<isize, String> {
+    diagnostic_code(program, 1)
 }
 
-pub fn process_b(text: &str) -> isize {
-    let mut computer = IntCodeComputer::from(text);
-    computer.push_input(5);
-    computer.process(ReturnEvent::HaltEvent);
-    let output =  computer.pop_all_output();
-    assert!(output.len() > 0);
+pub fn process_b(program: &Program) -> Result<isize, String> {
+    diagnostic_code(program, 5)
+}
+
+fn diagnostic_code(program: &Program, system_id: isize) -> Result<isize, String> {
+    let output = IntCodeComputer::from(program).run_with_inputs(vec![system_id])
+                                               .map_err(|error| error.to_string())?;
+    let (&code, test_results) = output.split_last().ok_or("The program halted without a diagnostic code")?;
     // Make sure all outputs except the last one are 0
-    assert!(&output[..output.len() - 1].iter().all(|&value| value == 0));
-    *output.last().unwrap()
-}
\ No newline at end of file
+    if test_results.iter().any(|&value| value != 0) {
+        return Err(format!("The diagnostic tests failed: {:?}", test_results));
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostic_code() {
+        // Outputs whatever it is given, after a passing test
+        let echo = parse_program("3,9,104,0,4,9,99,0,0,0").unwrap();
+
+        assert_eq!(diagnostic_code(&echo, 5), Ok(5));
+        assert_eq!(diagnostic_code(&parse_program("3,0,99").unwrap(), 5), Err(String::from("The program halted without a diagnostic code")));
+        assert_eq!(diagnostic_code(&parse_program("104,1,104,5,99").unwrap(), 5), Err(String::from("The diagnostic tests failed: [1]")));
+        assert_eq!(diagnostic_code(&parse_program("3,0,3,0,99").unwrap(), 5), Err(String::from("The program asked for more input than it was given")));
+    }
+}
@@ -1,47 +1,76 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
+use super::parse_error::*;
+use super::solution::*;
 
-pub fn process_a(text: &str) -> usize {
-    let graph = to_adjacency_list(text, GraphType::DirectedGraph);
+pub struct Solver;
 
+impl Solution for Solver {
+    const DAY: usize = 6;
+
+    type Input = Graph;
+    type Params = ();
+    type AnswerA = usize;
+    type AnswerB = usize;
+
+    fn parse(text: &str) -> Result<Graph, ParseError> {
+        to_adjacency_list(text)
+    }
+
+    fn part_a(input: &Graph, _params: &()) -> Result<usize, String> {
+        process_a(input)
+    }
+
+    fn part_b(input: &Graph, _params: &()) -> Result<usize, String> {
+        process_b(input)
+    }
+}
+
+// graph is a simple adjacency list of key -> connected keys
+type Graph = HashMap<String, Vec<String>>;
+
+pub fn process_a(graph: &Graph) -> Result<usize, String> {
     // Something to speed up the computation
     let mut orbit_count_cache: HashMap<String, usize> = HashMap::new();
 
-    graph.keys()
-         .fold(0, |sum, key| sum + orbit_count(key, &graph, &mut orbit_count_cache))
+    Ok(graph.keys()
+            .fold(0, |sum, key| sum + orbit_count(key, graph, &mut orbit_count_cache)))
 }
 
-pub fn process_b(text: &str) -> usize {
-    let graph = to_adjacency_list(text, GraphType::UndirectedGraph);
+pub fn process_b(graph: &Graph) -> Result<usize, String> {
+    // The transfers go from the object YOU orbit to the one SAN orbits
+    let start = center_of(graph, "YOU")?;
+    let target = center_of(graph, "SAN")?;
+    let graph = undirected(graph);
 
     // A simple breadth first search
     let mut keys_to_visit = VecDeque::new();
     let mut visited_keys = HashSet::new();
 
-    keys_to_visit.push_back(("YOU", 0));
+    keys_to_visit.push_back((start, 0));
+    visited_keys.insert(start);
 
-    while keys_to_visit.len() > 0 {
-        let (key, steps) =  keys_to_visit.pop_front().unwrap();
-
-        visited_keys.insert(key);
+    while let Some((key, steps)) = keys_to_visit.pop_front() {
+        if key == target {
+            return Ok(steps);
+        }
 
-        if graph.contains_key(key) {
-            for item in graph.get(key).unwrap().iter() {
-                if item == "SAN" {
-                    // -1 because we want to start from the orbit connected to YOU and we started from YOU
-                    return steps - 1;
-                }
-                else {
-                    if !visited_keys.contains(&item.as_ref()) {
-                        keys_to_visit.push_back((item, 1 + steps))
-                    }
-                }
+        for item in graph.get(key).into_iter().flatten() {
+            if visited_keys.insert(item.as_str()) {
+                keys_to_visit.push_back((item.as_str(), 1 + steps))
             }
         }
     }
 
-    return 0;
+    Err(String::from("YOU and SAN are not in the same system of orbits"))
+}
+
+fn center_of<'a>(graph: &'a Graph, object: &str) -> Result<&'a str, String> {
+    graph.get(object)
+         .and_then(|centers| centers.first())
+         .map(|center| center.as_str())
+         .ok_or_else(|| format!("{} is not orbiting anything", object))
 }
 
 fn orbit_count(key: &str, graph: &HashMap<String, Vec<String>>, cache: &mut HashMap<String, usize>) -> usize {
@@ -60,46 +89,63 @@ fn orbit_count(key: &str, graph: &HashMap<String, Vec<String>>, cache: &mut Hash
     }
 }
 
-#[derive(PartialEq)]
-enum GraphType {
-    DirectedGraph,
-    UndirectedGraph
-}
-
-fn to_adjacency_list(text: &str, graph_type: GraphType) -> HashMap<String, Vec<String>> {
+// Each object connected to the one it orbits
+fn to_adjacency_list(text: &str) -> Result<Graph, ParseError> {
     // Link is the vector of edges in the graph
     // A)B => [(B, A)] => B Orbits around A
     let links = text.lines()
-                    .filter(|&line| line.len() > 0)
-                    .map(|line| {
-                        let components = line.split(')')
-                                             .collect::<Vec<&str>>();
-                        (String::from(components[1]), String::from(components[0]))
+                    .enumerate()
+                    .filter(|(_, line)| !line.trim().is_empty())
+                    .map(|(i, line)| {
+                        let mut components = line.trim().splitn(2, ')');
+                        match (components.next(), components.next()) {
+                            (Some(center), Some(object)) if !center.is_empty() && !object.is_empty() =>
+                                Ok((String::from(object), String::from(center))),
+                            _ => Err(ParseError::new(i + 1, 1, &format!("Expected an orbit like A)B, got: {}", line.trim())))
+                        }
                     })
-                    .collect::<Vec<(String, String)>>();
+                    .collect::<Result<Vec<(String, String)>, ParseError>>()?;
 
-    // graph is a simple adjacency list of key -> connected keys
-    let mut graph: HashMap<String, Vec<String>> = HashMap::new();
+    let mut graph: Graph = HashMap::new();
 
-    for (key, value) in links.iter() {
-        if graph.contains_key(key) {
-            graph.get_mut(key).unwrap().push(value.to_string());
-        }
-        else {
-            graph.insert(key.to_string(), vec![value.to_string()]);
+    for (i, (key, value)) in links.iter().enumerate() {
+        if let Some(centers) = graph.get(key) {
+            return Err(ParseError::new(i + 1, 1, &format!("{} can not orbit both {} and {}", key, centers[0], value)));
         }
+        graph.insert(key.to_string(), vec![value.to_string()]);
+    }
 
-        if graph_type == GraphType::UndirectedGraph {
-            if graph.contains_key(value) {
-                graph.get_mut(value).unwrap().push(key.to_string());
-            }
-            else {
-                graph.insert(value.to_string(), vec![key.to_string()]);
+    // With a single center each, going from center to center either reaches an object orbiting nothing,
+    // or comes back around in fewer steps than there are objects
+    for (i, (key, _)) in links.iter().enumerate() {
+        let mut current = key;
+        for _ in 0..links.len() {
+            match graph.get(current) {
+                Some(centers) if &centers[0] == key => {
+                    return Err(ParseError::new(i + 1, 1, &format!("{} ends up orbiting itself", key)));
+                },
+                Some(centers) => current = &centers[0],
+                None => break
             }
         }
     }
 
-    graph
+    Ok(graph)
+}
+
+// The same connections, both ways
+fn undirected(graph: &Graph) -> Graph {
+    let mut undirected = graph.clone();
+
+    for (key, values) in graph.iter() {
+        for value in values.iter() {
+            undirected.entry(value.to_string())
+                      .or_default()
+                      .push(key.to_string());
+        }
+    }
+
+    undirected
 }
 
 #[cfg(test)]
@@ -108,7 +154,7 @@ mod tests {
     #[test]
     fn test_a() {
         // For a mass of 12, divide by 3 and round down to get 4, then subtract 2 to get 2.
-        assert_eq!(process_a("\
+        assert_eq!(process_a(&to_adjacency_list("\
         COM)B\n\
         B)C\n\
         C)D\n\
@@ -119,12 +165,16 @@ mod tests {
         D)I\n\
         E)J\n\
         J)K\n\
-        K)L"), 42);
+        K)L").unwrap()), Ok(42));
+
+        assert_eq!(to_adjacency_list("COM)B\nB-C"), Err(ParseError::new(2, 1, "Expected an orbit like A)B, got: B-C")));
+        assert_eq!(to_adjacency_list("COM)B\nA)B"), Err(ParseError::new(2, 1, "B can not orbit both COM and A")));
+        assert_eq!(to_adjacency_list("A)B\nB)A"), Err(ParseError::new(1, 1, "B ends up orbiting itself")));
     }
 
     #[test]
     fn test_b() {
-        assert_eq!(process_b("\
+        assert_eq!(process_b(&to_adjacency_list("\
         COM)B\n\
         B)C\n\
         C)D\n\
@@ -137,6 +187,13 @@ mod tests {
         J)K\n\
         K)L\n\
         K)YOU\n\
-        I)SAN"), 4);
+        I)SAN").unwrap()), Ok(4));
+
+        assert_eq!(process_b(&to_adjacency_list("COM)B\nB)C").unwrap()), Err(String::from("YOU is not orbiting anything")));
+        // Both in orbit around B, no transfer needed
+        assert_eq!(process_b(&to_adjacency_list("COM)B\nB)YOU\nB)SAN").unwrap()), Ok(0));
+        assert_eq!(process_b(&to_adjacency_list("SAN)YOU").unwrap()), Err(String::from("SAN is not orbiting anything")));
+        // YOU orbiting SAN, one transfer down to what SAN orbits
+        assert_eq!(process_b(&to_adjacency_list("COM)B\nB)SAN\nSAN)YOU").unwrap()), Ok(1));
     }
 }
\ No newline at end of file
@@ -1,42 +1,113 @@
-pub fn process_a(text: &str, width: usize, height: usize ) -> usize {
+use super::parse_error::*;
+use super::solution::*;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 8;
+
+    type Input = Vec<u8>;
+    type Params = ImageSize;
+    type AnswerA = usize;
+    type AnswerB = String;
+
+    fn parse(text: &str) -> Result<Vec<u8>, ParseError> {
+        pixels(text)
+    }
+
+    fn part_a(input: &Vec<u8>, params: &ImageSize) -> Result<usize, String> {
+        process_a(input, params.width, params.height)
+    }
+
+    fn part_b(input: &Vec<u8>, params: &ImageSize) -> Result<String, String> {
+        process_b(input, params.width, params.height)
+    }
+}
+
+/// The size of the image's layers, in pixels
+pub struct ImageSize {
+    pub width: usize,
+    pub height: usize
+}
+
+impl Default for ImageSize {
+    fn default() -> Self {
+        ImageSize { width: 25, height: 6 }
+    }
+}
+
+impl Params for ImageSize {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "width" => self.width = parse_param(key, value)?,
+            "height" => self.height = parse_param(key, value)?,
+            _ => return Err(format!("Unknown parameter: {}", key))
+        }
+
+        Ok(())
+    }
+}
+
+// The digits of the image, one layer after the other
+fn pixels(text: &str) -> Result<Vec<u8>, ParseError> {
+    text.trim()
+        .chars()
+        .enumerate()
+        .map(|(column, c)| c.to_digit(10)
+                            .map(|digit| digit as u8)
+                            .ok_or_else(|| ParseError::new(1, column + 1, &format!("Expected a digit, got: {}", c))))
+        .collect()
+}
+
+// Only the image's size tells how many layers there are
+fn check_layers(pixels: &[u8], width: usize, height: usize) -> Result<(), String> {
     let layer_length = width*height;
-    let layer_count = text.len()/layer_length;
-    let count_of = |s: &str, c| s.bytes()
-                                 .filter(|&b| b == c)
-                                 .count();
-    let zero_counts = (0..layer_count).map(|i| count_of(&text[i*layer_length..(i*layer_length+layer_length)], '0' as u8))
-                                      .collect::<Vec<usize>>();
-    let i_min_zeroes = zero_counts.iter().enumerate().min_by_key(|&(_, value)| value).unwrap().0;
-    let layer_text = &text[i_min_zeroes*layer_length..(i_min_zeroes*layer_length + layer_length)];
 
-    count_of(layer_text, '1' as u8)*count_of(layer_text, '2' as u8)
+    if layer_length == 0 || pixels.is_empty() || pixels.len() % layer_length != 0 {
+        return Err(format!("Expected layers of {}x{} digits, got {} digits", width, height, pixels.len()));
+    }
+
+    Ok(())
 }
 
-pub fn process_b(text: &str, width: usize, height: usize ) -> String {
+pub fn process_a(pixels: &[u8], width: usize, height: usize ) -> Result<usize, String> {
+    check_layers(pixels, width, height)?;
+    let count_of = |layer: &[u8], digit| layer.iter()
+                                              .filter(|&&pixel| pixel == digit)
+                                              .count();
+    let layer = pixels.chunks(width*height)
+                      .min_by_key(|layer| count_of(layer, 0))
+                      .unwrap();
+
+    Ok(count_of(layer, 1)*count_of(layer, 2))
+}
+
+pub fn process_b(pixels: &[u8], width: usize, height: usize ) -> Result<String, String> {
+    check_layers(pixels, width, height)?;
     let layer_length = width*height;
-    let layer_count = text.len()/layer_length;
+    let layer_count = pixels.len()/layer_length;
     // The merged layers in themselves aren't the solution for this problem
     let merged_layers = (0..layer_length).map(|i| {
-                                                (0..layer_count).fold('2', |current, layer_index| {
-                                                    if current != '2' {
+                                                (0..layer_count).fold(2, |current, layer_index| {
+                                                    if current != 2 {
                                                         current
                                                     } else {
-                                                        text.as_bytes()[layer_length*layer_index + i] as char
+                                                        pixels[layer_length*layer_index + i]
                                                     }
                                                 })
-                                            }).collect::<String>();
+                                            }).collect::<Vec<u8>>();
 
     // It is for the user to interpret the bitmap as readable text
     // Hence we return a printable result
-    let tmp = merged_layers.bytes()
-                           .map(|c| if c as char == '1' { '#' } else {  ' ' })
+    let tmp = merged_layers.iter()
+                           .map(|&pixel| if pixel == 1 { '#' } else {  ' ' })
                           .collect::<String>();
 
     // We want to make sure that the printable_result starts on a new line. Hence the \n at the beginning of a line
     let printable_result = (0..height).map(|i| String::from("\n") + &tmp[width*i..width*i + width])
                                       .collect::<String>();
 
-    printable_result
+    Ok(printable_result)
 }
 
 #[cfg(test)]
@@ -44,12 +115,15 @@ mod tests {
     use super::*;
     #[test]
     fn test_a() {
-        assert_eq!(process_a("123456789012", 3, 2), 1);
+        assert_eq!(process_a(&pixels("123456789012").unwrap(), 3, 2), Ok(1));
+
+        assert_eq!(process_a(&pixels("1234567890").unwrap(), 3, 2), Err(String::from("Expected layers of 3x2 digits, got 10 digits")));
+        assert_eq!(pixels("12345x789012"), Err(ParseError::new(1, 6, "Expected a digit, got: x")));
     }
 
     #[test]
     fn test_b() {
         // 0222112222120000 -> 0110
-        assert_eq!(process_b("0222112222120000", 2, 2), String::from("\n #\n# "));
+        assert_eq!(process_b(&pixels("0222112222120000").unwrap(), 2, 2), Ok(String::from("\n #\n# ")));
     }
 }
\ No newline at end of file
@@ -1,13 +1,38 @@
 use super::intcode_computer::*;
+use super::intcode_loader::*;
+use super::parse_error::*;
+use super::solution::*;
 
-pub fn process(text: &str, input: isize) -> isize {
-    let mut computer = IntCodeComputer::from(text);
-    computer.push_input(input);
-    computer.process(ReturnEvent::HaltEvent);
-
-    let output =  computer.pop_all_output();
-    assert!(output.len() > 0);
-    // Make sure all outputs except the last one are 0
-    assert!(&output[..output.len() - 1].iter().all(|&value| value == 0));
-    *output.last().unwrap()
-}
\ No newline at end of file
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 9;
+
+    type Input = Program;
+    type Params = ();
+    type AnswerA = isize;
+    type AnswerB = isize;
+
+    fn parse(text: &str) -> Result<Program, ParseError> {
+        parse_program(text)
+    }
+
+    fn part_a(input: &Program, _params: &()) -> Result<isize, String> {
+        process(input, 1)
+    }
+
+    fn part_b(input: &Program, _params: &()) -> Result<isize, String> {
+        process(input, 2)
+    }
+}
+
+pub fn process(program: &Program, input: isize) -> Result<isize, String> {
+    let output = IntCodeComputer::from(program).run_with_inputs(vec![input])
+                                               .map_err(|error| error.to_string())?;
+    let (&keycode, test_results) = output.split_last().ok_or("The program halted without a BOOST keycode")?;
+    // Make sure all outputs except the last one are 0, else they are the opcodes that malfunctioned
+    if test_results.iter().any(|&value| value != 0) {
+        return Err(format!("The BOOST program found malfunctioning opcodes: {:?}", test_results));
+    }
+    Ok(keycode)
+}
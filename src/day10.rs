@@ -1,17 +1,78 @@
 use num_integer::gcd;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use super::parse_error::*;
+use super::solution::*;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 10;
+
+    type Input = Vec<Pair>;
+    type Params = Laser;
+    type AnswerA = usize;
+    type AnswerB = isize;
+
+    fn parse(text: &str) -> Result<Vec<Pair>, ParseError> {
+        asteroid_coordinates(text)
+    }
+
+    fn part_a(input: &Vec<Pair>, _params: &Laser) -> Result<usize, String> {
+        process_a(input)
+    }
+
+    fn part_b(input: &Vec<Pair>, params: &Laser) -> Result<isize, String> {
+        process_b(input, params.station, params.target)
+    }
+}
+
+/// Where the monitoring station is, and which vaporized asteroid we are after (counting from 0)
+pub struct Laser {
+    pub station: Pair,
+    pub target: usize
+}
+
+impl Default for Laser {
+    fn default() -> Self {
+        // Asteroid at (37, 25) has the maximum visibility of 309 asteroids, as per a
+        // So directly inputting those coordinates, to reduce the burden on process_b
+        Laser { station: (37, 25), target: 199 }
+    }
+}
+
+impl Params for Laser {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "station" => {
+                let coordinates = value.split(',')
+                                       .map(|item| parse_param::<isize>(key, item))
+                                       .collect::<Result<Vec<isize>, String>>()?;
+                if coordinates.len() != 2 {
+                    return Err(format!("Expected x,y for {}, got: {}", key, value));
+                }
+                self.station = (coordinates[0], coordinates[1]);
+            },
+            "target" => self.target = parse_param(key, value)?,
+            _ => return Err(format!("Unknown parameter: {}", key))
+        }
+
+        Ok(())
+    }
+}
 
 // We are going to use this type to represent both slopes and points
 type Pair = (isize, isize);
 
-pub fn process_a(text: &str) -> usize {
-    let points = asteroid_coordinates(text);
+pub fn process_a(points: &[Pair]) -> Result<usize, String> {
+    if points.is_empty() {
+        return Err(String::from("No asteroid to put the station on"));
+    }
 
     // For each point, compute the number of unique slopes with other points
     let slopes_per_point = points.iter()
                                  .map(|p| {
-                                     slopes_with_point(*p, &points).iter()
+                                     slopes_with_point(*p, points).iter()
                                                                    .map(|item| item.1)
                                                                    .collect::<HashSet<Pair>>()
                                                                    .len()
@@ -22,15 +83,19 @@ pub fn process_a(text: &str) -> usize {
 
     // println!("Asteroid at {:?} has the maximum visibility of {} asteroids",
     //          asteroid_coordinates[point_with_max_slopes.0], *point_with_max_slopes.1);
-    *point_with_max_slopes.1
+    Ok(*point_with_max_slopes.1)
 }
 
-pub fn process_b(text: &str, start: (isize, isize), target: usize) -> isize {
-    let points = asteroid_coordinates(text);
-    let index_x = points.iter().position(|&p| p == start).unwrap();
+pub fn process_b(points: &[Pair], start: (isize, isize), target: usize) -> Result<isize, String> {
+    let index_x = points.iter()
+                        .position(|&p| p == start)
+                        .ok_or_else(|| format!("No asteroid at the station's position {},{}", start.0, start.1))?;
+    if points.len() <= target + 1 {
+        return Err(format!("Expected more than {} asteroids besides the station, got: {}", target, points.len() - 1));
+    }
     let x = points[index_x];
 
-    let mut slopes_of_points = slopes_with_point(x, &points);
+    let mut slopes_of_points = slopes_with_point(x, points);
 
     // Sort by distance from x
     slopes_of_points.sort_by_key(|&(index_p, _)| {
@@ -79,32 +144,34 @@ pub fn process_b(text: &str, start: (isize, isize), target: usize) -> isize {
         }
     }
 
-    assert!(vaporized_points.len() > target);
     let target_point = points[vaporized_points[target]];
-    100*target_point.0 + target_point.1
+    Ok(100*target_point.0 + target_point.1)
 }
 
 // Take the grid of .'s and #'s and return the coordinates(column, row) of points where #'s exist
-fn asteroid_coordinates(text: &str) -> Vec<Pair> {
-    text.lines()
-        .filter(|line| line.len() > 0)
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.trim()
-                .bytes()
-                .enumerate()
-                .map(|(column, c)| {
-                    if c as char == '#' { Some((column as isize, row as isize)) }
-                    else { None }
-                })
-                .filter_map(|item| item)
-                .collect::<Vec<Pair>>()
-            })
-        .collect::<Vec<Pair>>()
+fn asteroid_coordinates(text: &str) -> Result<Vec<Pair>, ParseError> {
+    let mut points = Vec::new();
+    // Rows are counted over the non blank lines, errors point at the actual line
+    let rows = text.lines()
+                   .enumerate()
+                   .filter(|(_, line)| !line.trim().is_empty());
+
+    for (row, (line_index, line)) in rows.enumerate() {
+        let cells = line.trim();
+        for (column, c) in cells.char_indices() {
+            match c {
+                '#' => points.push((column as isize, row as isize)),
+                '.' => (),
+                _ => return Err(ParseError::at(line_index + 1, line, &cells[column..], &format!("Expected # or ., got: {}", c)))
+            }
+        }
+    }
+
+    Ok(points)
 }
 
 // Returns a vector[(slope of a point, index of a point)]
-fn slopes_with_point(x: Pair, points: &Vec<Pair>) -> Vec<(usize, Pair)> {
+fn slopes_with_point(x: Pair, points: &[Pair]) -> Vec<(usize, Pair)> {
     points.iter()
           .enumerate()
           .filter_map(|(index_p, &p)| {
@@ -129,15 +196,15 @@ mod tests {
     #[test]
     fn test_a() {
         // Best is 3,4 because it can detect 8 asteroids
-        assert_eq!(process_a("\n\
+        assert_eq!(process_a(&asteroid_coordinates("\n\
         .#..#\n\
         .....\n\
         #####\n\
         ....#\n\
-        ...##"), 8);
+        ...##").unwrap()), Ok(8));
 
         // Best is 5,8 with 33 other asteroids detected
-        assert_eq!(process_a("\n\
+        assert_eq!(process_a(&asteroid_coordinates("\n\
         ......#.#.\n\
         #..#.#....\n\
         ..#######.\n\
@@ -147,10 +214,10 @@ mod tests {
         #..#....#.\n\
         .##.#..###\n\
         ##...#..#.\n\
-        .#....####"), 33);
+        .#....####").unwrap()), Ok(33));
 
         // Best is 6,3 with 41 other asteroids detected
-        assert_eq!(process_a("\n\
+        assert_eq!(process_a(&asteroid_coordinates("\n\
         .#..#..###\n\
         ####.###.#\n\
         ....###.#.\n\
@@ -160,10 +227,10 @@ mod tests {
         ..#.#..#.#\n\
         #..#.#.###\n\
         .##...##.#\n\
-        .....#.#.."), 41);
+        .....#.#..").unwrap()), Ok(41));
 
         // Best is 11,13 with 210 other asteroids detected
-        assert_eq!(process_a("\n\
+        assert_eq!(process_a(&asteroid_coordinates("\n\
         .#..##.###...#######\n\
         ##.############..##.\n\
         .#.######.########.#\n\
@@ -183,21 +250,23 @@ mod tests {
         ....##.##.###..#####\n\
         .#.#.###########.###\n\
         #.#.#.#####.####.###\n\
-        ###.##.####.##.#..##"), 210);
+        ###.##.####.##.#..##").unwrap()), Ok(210));
+
+        assert_eq!(asteroid_coordinates("\n.#..#\n..X..\n"), Err(ParseError::new(3, 3, "Expected # or ., got: X")));
     }
 
     #[test]
     fn test_b() {
-        assert_eq!(process_b("\n\
+        assert_eq!(process_b(&asteroid_coordinates("\n\
         .#....#####...#..\n\
         ##...##.#####..##\n\
         ##...#...#.#####.\n\
         ..#.....#...###..\n\
-        ..#.#.....#....##", (8, 3), 1), 900);
+        ..#.#.....#....##").unwrap(), (8, 3), 1), Ok(900));
 
         // Best is 11,13 with 210 other asteroids detected
         // The 200th asteroid to be vaporized is at 8,2
-        assert_eq!(process_b("\n\
+        assert_eq!(process_b(&asteroid_coordinates("\n\
         .#..##.###...#######\n\
         ##.############..##.\n\
         .#.######.########.#\n\
@@ -217,7 +286,9 @@ mod tests {
         ....##.##.###..#####\n\
         .#.#.###########.###\n\
         #.#.#.#####.####.###\n\
-        ###.##.####.##.#..##", (11, 13), 199), 802);
+        ###.##.####.##.#..##").unwrap(), (11, 13), 199), Ok(802));
+
+        assert_eq!(process_b(&asteroid_coordinates(".#\n#.").unwrap(), (0, 0), 1), Err(String::from("No asteroid at the station's position 0,0")));
     }
 
 }
\ No newline at end of file
@@ -1,5 +1,31 @@
 use super::intcode_computer::*;
+use super::intcode_loader::*;
 use std::collections::HashMap;
+use super::parse_error::*;
+use super::solution::*;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 11;
+
+    type Input = Program;
+    type Params = ();
+    type AnswerA = usize;
+    type AnswerB = String;
+
+    fn parse(text: &str) -> Result<Program, ParseError> {
+        parse_program(text)
+    }
+
+    fn part_a(input: &Program, _params: &()) -> Result<usize, String> {
+        process_a(input)
+    }
+
+    fn part_b(input: &Program, _params: &()) -> Result<String, String> {
+        process_b(input)
+    }
+}
 
 type Pair = (isize, isize);
 type Color = isize;
@@ -7,12 +33,12 @@ type Color = isize;
 const BLACK: isize = 0;
 const WHITE: isize = 1;
 
-pub fn process_a(text: &str) -> usize {
-    run_robot(text, BLACK).len()
+pub fn process_a(program: &Program) -> Result<usize, String> {
+    Ok(run_robot(program, BLACK)?.len())
 }
 
-pub fn process_b(text: &str) -> String {
-    let panels = run_robot(text, WHITE);
+pub fn process_b(program: &Program) -> Result<String, String> {
+    let panels = run_robot(program, WHITE)?;
     let min_x = panels.keys().min_by_key(|&item| item.0).unwrap().0;
     let min_y = panels.keys().min_by_key(|&item| item.1).unwrap().1;
     let max_x = panels.keys().max_by_key(|&item| item.0).unwrap().0;
@@ -29,13 +55,13 @@ pub fn process_b(text: &str) -> String {
     }
 
     // Reversing the string for better printing
-    (0..height).rev().map(|row| String::from("\n") + &canvas[row].iter().collect::<String>())
-               .collect::<String>()
+    Ok((0..height).rev().map(|row| String::from("\n") + &canvas[row].iter().collect::<String>())
+                  .collect::<String>())
 }
 
 // Returns all the panels that are painted by the robot
-fn run_robot(instructions: &str, initial_input: isize) -> HashMap<Pair, Color> {
-    let mut computer = IntCodeComputer::from(instructions);
+fn run_robot(program: &Program, initial_input: isize) -> Result<HashMap<Pair, Color>, String> {
+    let mut computer = IntCodeComputer::from(program);
     let mut panels: HashMap<Pair, isize> = HashMap::new();
     let mut current_location: Pair = (0,0);
     let mut current_angle: f64 = 90.0;
@@ -61,10 +87,7 @@ fn run_robot(instructions: &str, initial_input: isize) -> HashMap<Pair, Color> {
             match computer.pop_output() {
                 0 => current_angle += 90.0,
                 1 => current_angle -= 90.0,
-                _ => {
-                    println!("Invalid turn direction received!");
-                    break;
-                }
+                turn => return Err(format!("Invalid turn direction received: {}", turn))
             }
 
             let direction = (current_angle.to_radians().cos() as isize, current_angle.to_radians().sin() as isize);
@@ -72,7 +95,7 @@ fn run_robot(instructions: &str, initial_input: isize) -> HashMap<Pair, Color> {
         }
     }
 
-    panels
+    Ok(panels)
 }
 
 // No good unit tests today :(
\ No newline at end of file
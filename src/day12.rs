@@ -1,16 +1,70 @@
-use std::convert::From;
 use std::cell::RefCell;
 use std::collections::HashSet;
 use num_integer::lcm;
+use super::parse_error::*;
+use super::solution::*;
 
-pub fn process_a(text: &str, steps: usize) -> usize {
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 12;
+
+    type Input = Vec<Moon>;
+    type Params = Simulation;
+    type AnswerA = usize;
+    type AnswerB = usize;
+
+    fn parse(text: &str) -> Result<Vec<Moon>, ParseError> {
+        moons(text)
+    }
+
+    fn part_a(input: &Vec<Moon>, params: &Simulation) -> Result<usize, String> {
+        process_a(input, params.steps)
+    }
+
+    fn part_b(input: &Vec<Moon>, _params: &Simulation) -> Result<usize, String> {
+        process_b(input)
+    }
+}
+
+/// How many steps part a simulates
+pub struct Simulation {
+    pub steps: usize
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Simulation { steps: 1000 }
+    }
+}
+
+impl Params for Simulation {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "steps" => self.steps = parse_param(key, value)?,
+            _ => return Err(format!("Unknown parameter: {}", key))
+        }
+
+        Ok(())
+    }
+}
+
+// One moon per line
+fn moons(text: &str) -> Result<Vec<Moon>, ParseError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| Moon::parse(i + 1, line))
+        .collect()
+}
+
+pub fn process_a(moons: &[Moon], steps: usize) -> Result<usize, String> {
     // TODO: Is there a better way to do this than to use a RefCell?
     // Had to use a RefCell so as to be able to do:
     // moons[i].borrow_mut().apply_gravity(&moons[j].borrow());
-    let moons = text.lines()
-                    .filter(|line| line.len() > 0)
-                    .map(|line| RefCell::new(Moon::from(line.trim())))
-                    .collect::<Vec<RefCell<Moon>>>();
+    let moons = moons.iter()
+                     .map(|moon| RefCell::new(moon.clone()))
+                     .collect::<Vec<RefCell<Moon>>>();
 
     for _ in 0..steps {
 
@@ -30,17 +84,12 @@ pub fn process_a(text: &str, steps: usize) -> usize {
 
     }
 
-    moons.iter()
-         .map(|moon| moon.borrow().total_energy())
-         .sum::<usize>()
+    Ok(moons.iter()
+            .map(|moon| moon.borrow().total_energy())
+            .sum::<usize>())
 }
 
-pub fn process_b(text: &str) -> usize {
-    let moons = text.lines()
-                    .filter(|line| line.len() > 0)
-                    .map(|line| Moon::from(line.trim()))
-                    .collect::<Vec<Moon>>();
-
+pub fn process_b(moons: &[Moon]) -> Result<usize, String> {
     // TODO: Can this function be done better/faster using some ninja math?
     fn cycle_time(positions: Vec<isize>) -> usize {
         let mut state = positions.iter()
@@ -92,8 +141,8 @@ pub fn process_b(text: &str) -> usize {
                                     })
                             .collect::<Vec<usize>>();
 
-    cycle_times.iter()
-               .fold(cycle_times[0], |current_lcm, &value| lcm(current_lcm, value))
+    Ok(cycle_times.iter()
+                  .fold(cycle_times[0], |current_lcm, &value| lcm(current_lcm, value)))
 }
 
 type Vector3 = [isize; 3];
@@ -104,8 +153,8 @@ fn signum(value: isize) -> isize {
     else { -1 }
 }
 
-#[derive(Debug)]
-struct Moon {
+#[derive(Debug, Clone, PartialEq)]
+pub struct Moon {
     position: Vector3,
     velocity: Vector3
 }
@@ -130,23 +179,31 @@ impl Moon {
     }
 }
 
-impl From<&str> for Moon {
+impl Moon {
     /// Constructs a moon from a line like: "<x=-1, y=0, z=2>"
-    fn from(text: &str) -> Self {
-        assert!(text.len() > 2);
-        let t = text[1..text.len() - 1].split(',')
-                                       .filter(|s| s.len() > 0)
-                                       .map(|item| item.split('=')
-                                                       .nth(1)
-                                                       .unwrap()
-                                                       .trim()
-                                                       .parse::<isize>().unwrap())
-                                       .collect::<Vec<isize>>();
-        assert!(t.len() == 3);
-        Moon {
-            position: [t[0], t[1], t[2]],
-            velocity: [0, 0, 0]
+    fn parse(line_number: usize, line: &str) -> Result<Moon, ParseError> {
+        let text = line.trim();
+        let expected = || ParseError::at(line_number, line, text, &format!("Expected a moon like <x=-1, y=0, z=2>, got: {}", text));
+
+        let coordinates = text.strip_prefix('<')
+                              .and_then(|text| text.strip_suffix('>'))
+                              .ok_or_else(expected)?
+                              .split(',')
+                              .collect::<Vec<&str>>();
+        if coordinates.len() != 3 {
+            return Err(expected());
         }
+
+        let mut position = [0; 3];
+        for (i, (&item, axis)) in coordinates.iter().zip(["x", "y", "z"].iter()).enumerate() {
+            let item = item.trim();
+            let value = item.strip_prefix(axis)
+                            .and_then(|item| item.strip_prefix('='))
+                            .ok_or_else(|| ParseError::at(line_number, line, item, &format!("Expected {}=, got: {}", axis, item)))?;
+            position[i] = parse_token(line_number, line, value.trim(), "an integer")?;
+        }
+
+        Ok(Moon { position, velocity: [0, 0, 0] })
     }
 }
 
@@ -157,30 +214,33 @@ mod tests {
     fn test_a() {
 
         // Example 1
-        assert_eq!(process_a("<x=-1, y=0, z=2>\n\
+        assert_eq!(process_a(&moons("<x=-1, y=0, z=2>\n\
         <x=2, y=-10, z=-7>\n\
         <x=4, y=-8, z=8>\n\
-        <x=3, y=5, z=-1>", 10), 179);
+        <x=3, y=5, z=-1>").unwrap(), 10), Ok(179));
 
 
-        assert_eq!(process_a("<x=-8, y=-10, z=0>\n\
+        assert_eq!(process_a(&moons("<x=-8, y=-10, z=0>\n\
         <x=5, y=5, z=10>\n\
         <x=2, y=-7, z=3>\n\
-        <x=9, y=-8, z=-3>", 100), 1940);
+        <x=9, y=-8, z=-3>").unwrap(), 100), Ok(1940));
+
+        assert_eq!(moons("<x=-1, y=0, z=2>\n<x=2, y=ten, z=-7>"), Err(ParseError::new(2, 9, "Expected an integer, got: ten")));
+        assert_eq!(moons("<x=-1, y=0, z=2>\n<x=2, z=-7>"), Err(ParseError::new(2, 1, "Expected a moon like <x=-1, y=0, z=2>, got: <x=2, z=-7>")));
     }
 
     #[test]
     fn test_b() {
 
-        assert_eq!(process_b("<x=-1, y=0, z=2>\n\
+        assert_eq!(process_b(&moons("<x=-1, y=0, z=2>\n\
         <x=2, y=-10, z=-7>\n\
         <x=4, y=-8, z=8>\n\
-        <x=3, y=5, z=-1>"), 2772);
+        <x=3, y=5, z=-1>").unwrap()), Ok(2772));
 
 
-        assert_eq!(process_b("<x=-8, y=-10, z=0>\n\
+        assert_eq!(process_b(&moons("<x=-8, y=-10, z=0>\n\
         <x=5, y=5, z=10>\n\
         <x=2, y=-7, z=3>\n\
-        <x=9, y=-8, z=-3>"), 4686774924);
+        <x=9, y=-8, z=-3>").unwrap()), Ok(4686774924));
     }
 }
\ No newline at end of file
@@ -1,6 +1,32 @@
 use std::collections::HashMap;
 
 use super::intcode_computer::*;
+use super::intcode_loader::*;
+use super::parse_error::*;
+use super::solution::*;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 13;
+
+    type Input = Program;
+    type Params = ();
+    type AnswerA = usize;
+    type AnswerB = isize;
+
+    fn parse(text: &str) -> Result<Program, ParseError> {
+        parse_program(text)
+    }
+
+    fn part_a(input: &Program, _params: &()) -> Result<usize, String> {
+        process_a(input)
+    }
+
+    fn part_b(input: &Program, _params: &()) -> Result<isize, String> {
+        process_b(input)
+    }
+}
 
 type Pair = (isize, isize);
 type Tile = isize;
@@ -12,21 +38,26 @@ const HORIZONTAL_PADDLE: isize = 3;
 const BALL: isize = 4;
 
 
-pub fn process_a(instructions: &str) -> usize {
-    let mut computer = IntCodeComputer::from(instructions);
-    computer.process(ReturnEvent::HaltEvent);
+pub fn process_a(program: &Program) -> Result<usize, String> {
+    let mut computer = IntCodeComputer::from(program);
 
-    get_coordinate_values(computer.pop_all_output()).values()
-                                                    .filter(|&v| *v == BLOCK).count()
+    Ok(get_coordinate_values(computer.outputs_in_triples()).values()
+                                                           .filter(|&v| *v == BLOCK).count())
 }
 
-pub fn process_b(instructions: &str) -> isize {
-    let mut computer = IntCodeComputer::from(instructions);
+pub fn process_b(program: &Program) -> Result<isize, String> {
+    play(&mut free_play(program))
+}
 
+fn free_play(program: &Program) -> IntCodeComputer {
+    let mut computer = IntCodeComputer::from(program);
     computer.set_memory_value(0, 2);
-    computer.process(ReturnEvent::InputNeededEvent);
+    computer
+}
 
-    let mut canvas = get_coordinate_values(computer.pop_all_output());
+// Plays the game until every block is broken, returns the score
+fn play(computer: &mut IntCodeComputer) -> Result<isize, String> {
+    let mut canvas = get_coordinate_values(computer.outputs_in_triples());
     let mut block_count = canvas.values().filter(|&v| *v == BLOCK).count();
     let mut paddle_position = get_location(&canvas, HORIZONTAL_PADDLE, &(0, 0));
     let mut ball_position = get_location(&canvas, BALL, &(0, 0));
@@ -42,18 +73,13 @@ pub fn process_b(instructions: &str) -> isize {
                                 else { -1 };
 
         computer.push_input(joystick_position);
-        computer.process(ReturnEvent::InputNeededEvent);
 
         // The output we get here is not for the full canvas but only what changes it seems
-        let output = computer.pop_all_output();
-
-        for i in 0..output.len()/3 {
-            let (x, y, value) = (output[3*i], output[3*i +1], output[3*i + 2]);
-
+        for (x, y, value) in computer.outputs_in_triples() {
             if (x, y) == (-1, 0) {
                 score = value;
             } else {
-                if *canvas.get(&(x, y)).unwrap() == BLOCK {
+                if canvas.get(&(x, y)) == Some(&BLOCK) {
                     block_count -= 1;
                 }
 
@@ -70,12 +96,17 @@ pub fn process_b(instructions: &str) -> isize {
         // print_canvas(&canvas);
     }
 
-    score
+    // The score only counts once every block is broken
+    if block_count > 0 {
+        return Err(format!("Game over, blocks left: {}, score: {}", block_count, score));
+    }
+
+    Ok(score)
 }
 
-fn get_coordinate_values(output: Vec<isize>) -> HashMap<Pair, Tile> {
-    (0..(output.len()/3)).map(|i| ((output[3*i], output[3*i+1]), output[3*i+2]))
-                         .collect::<HashMap<Pair, Tile>>()
+fn get_coordinate_values<I: Iterator<Item = (isize, isize, Tile)>>(output: I) -> HashMap<Pair, Tile> {
+    output.map(|(x, y, tile)| ((x, y), tile))
+          .collect::<HashMap<Pair, Tile>>()
 }
 
 fn get_location(coordinate_values: &HashMap<Pair, Tile>, tile: isize, default_position: &Pair) -> Pair {
@@ -119,4 +150,37 @@ fn print_canvas(panels: &HashMap<Pair, Tile>) {
                               .collect::<String>());
 }
 
-// No unit tests today either :(
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode_recording::*;
+
+    #[test]
+    fn test_opening_replay() {
+        let program = parse_program(&std::fs::read_to_string("testdata/day13/input.txt").unwrap()).unwrap();
+        let recording = Recording::parse(&std::fs::read_to_string("testdata/day13/opening.rec").unwrap()).unwrap();
+
+        assert_eq!(replay(&mut free_play(&program), &recording), Ok(()));
+        assert_eq!(recording.inputs().len(), 100);
+
+        // The last thing drawn is the score after the blocks broken so far
+        assert_eq!(recording.outputs().rchunks(3).find(|triple| triple[..2] == [-1, 0]), Some(&[-1, 0, 896][..]));
+
+        // Playing the same way records the same session
+        let mut computer = free_play(&program);
+        computer.start_recording();
+        play(&mut computer).unwrap();
+        let mut events = computer.take_recording().unwrap().events;
+        events.truncate(recording.events.len());
+        assert_eq!(events, recording.events);
+    }
+
+    #[test]
+    fn test_game_over() {
+        // Draws a single block at 1,1 and quits without waiting for the joystick
+        let program = parse_program("1,0,0,20,104,1,104,1,104,2,99").unwrap();
+
+        assert_eq!(process_a(&program), Ok(1));
+        assert_eq!(process_b(&program), Err(String::from("Game over, blocks left: 1, score: 0")));
+    }
+}
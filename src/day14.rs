@@ -1,110 +1,187 @@
 use regex::Regex;
 use std::collections::HashMap;
+use super::parse_error::*;
+use super::solution::*;
 
-pub fn process_a(text: &str) -> u64 {
-    get_ore_required(&get_elements(text), &mut HashMap::new(), &String::from("FUEL"), 1)
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 14;
+
+    type Input = HashMap<String, Element>;
+    type Params = ();
+    type AnswerA = u64;
+    type AnswerB = u64;
+
+    fn parse(text: &str) -> Result<HashMap<String, Element>, ParseError> {
+        get_elements(text)
+    }
+
+    fn part_a(input: &HashMap<String, Element>, _params: &()) -> Result<u64, String> {
+        process_a(input)
+    }
+
+    fn part_b(input: &HashMap<String, Element>, _params: &()) -> Result<u64, String> {
+        process_b(input)
+    }
+}
+
+pub fn process_a(elements: &HashMap<String, Element>) -> Result<u64, String> {
+    get_ore_required(elements, &mut HashMap::new(), "FUEL", 1)
+        .ok_or_else(|| String::from("The ore needed doesn't fit in 64 bits"))
 }
 
-pub fn process_b(text: &str) -> u64 {
-    let elements = get_elements(text);
+pub fn process_b(elements: &HashMap<String, Element>) -> Result<u64, String> {
     let available_ore = 1000000000000 as u64;
+    let can_produce = |fuel| matches!(get_ore_required(elements, &mut HashMap::new(), "FUEL", fuel), Some(ore) if ore <= available_ore);
+
+    // Leftovers can make a unit of ore yield more than a unit of fuel, so the upper bound is found by doubling
+    let mut maximum_fuel = 1 as u64;
+    while can_produce(maximum_fuel) {
+        maximum_fuel = maximum_fuel.checked_mul(2)
+                                   .ok_or_else(|| String::from("The fuel produced doesn't fit in 64 bits"))?;
+    }
 
+    // Then simply binary search, minimum_fuel can always be produced and maximum_fuel never can.
+    // It should still take like less than 64 turns to get the answer we need
     let mut minimum_fuel = 0 as u64;
-    let mut maximum_fuel = 1000000000000 as u64;
-    let mut maximum_fuel_produced = 0;
-
-    // Given a function that tells us how much ore we need to produce a given quantity of fuel, simply binary search
-    // from 0 to available ore. It should still take like less than 64 turns to get the answer we need
-    while minimum_fuel < maximum_fuel {
-        let fuel_to_produce = (maximum_fuel + minimum_fuel)/2;
-        let ore_required = get_ore_required(&elements, &mut HashMap::new(), &String::from("FUEL"), fuel_to_produce);
-
-        if ore_required == available_ore {
-            maximum_fuel_produced = fuel_to_produce;
-            break;
-        } else if ore_required > available_ore {
-            maximum_fuel = fuel_to_produce - 1;
+    while maximum_fuel - minimum_fuel > 1 {
+        let fuel_to_produce = minimum_fuel + (maximum_fuel - minimum_fuel)/2;
+
+        if can_produce(fuel_to_produce) {
+            minimum_fuel = fuel_to_produce;
         } else {
-            minimum_fuel = fuel_to_produce + 1;
-            maximum_fuel_produced = std::cmp::max(fuel_to_produce, maximum_fuel_produced);
+            maximum_fuel = fuel_to_produce;
         }
     }
 
-    maximum_fuel_produced
+    Ok(minimum_fuel)
 }
 
-fn get_elements(text: &str) -> HashMap<String, Element> {
-    text.lines()
-        .filter(|line| line.trim().len() > 0)
-        .map(|line| {
-                        let e = Element::from(line);
-                        (e.name.clone(), e)
-                    })
-        .collect::<HashMap<String, Element>>()
+// The reactions by the element they produce, every element they need being either ORE or produced by one of them
+fn get_elements(text: &str) -> Result<HashMap<String, Element>, ParseError> {
+    let reactions = text.lines()
+                        .enumerate()
+                        .filter(|(_, line)| !line.trim().is_empty())
+                        .map(|(i, line)| Ok((i + 1, Element::parse(i + 1, line)?)))
+                        .collect::<Result<Vec<(usize, Element)>, ParseError>>()?;
+
+    for (line_number, element) in reactions.iter() {
+        for (_, name) in element.elements_required.iter() {
+            if name != "ORE" && !reactions.iter().any(|(_, e)| &e.name == name) {
+                return Err(ParseError::new(*line_number, 1, &format!("No reaction produces {}", name)));
+            }
+        }
+    }
+
+    if !reactions.iter().any(|(_, e)| e.name == "FUEL") {
+        return Err(ParseError::new(1, 1, "No reaction produces FUEL"));
+    }
 
+    for (i, (line_number, element)) in reactions.iter().enumerate() {
+        if element.name == "ORE" {
+            return Err(ParseError::new(*line_number, 1, "ORE can not be produced, it is what everything is made of"));
+        }
+        if reactions[..i].iter().any(|(_, e)| e.name == element.name) {
+            return Err(ParseError::new(*line_number, 1, &format!("More than one reaction produces {}", element.name)));
+        }
+    }
+
+    // Resolving the elements that can be made out of ORE and the ones resolved so far, until none is left.
+    // Those left over need themselves to be produced
+    let mut resolved = vec!["ORE"];
+    let mut unresolved = reactions.iter().collect::<Vec<&(usize, Element)>>();
+
+    while !unresolved.is_empty() {
+        let (ready, waiting): (Vec<_>, Vec<_>) = unresolved.into_iter()
+                                                           .partition(|(_, e)| e.elements_required.iter().all(|(_, name)| resolved.contains(&name.as_str())));
+        if ready.is_empty() {
+            let (line_number, element) = waiting[0];
+            return Err(ParseError::new(*line_number, 1, &format!("{} can not be produced from ORE, its reactions go round in a circle", element.name)));
+        }
+
+        resolved.extend(ready.iter().map(|(_, e)| e.name.as_str()));
+        unresolved = waiting;
+    }
+
+    Ok(reactions.into_iter()
+                .map(|(_, e)| (e.name.clone(), e))
+                .collect::<HashMap<String, Element>>())
 }
 
+// None when the quantities involved don't fit in 64 bits
 fn get_ore_required(elements: &HashMap<String, Element>,
                     available_elements: &mut HashMap<String, u64>,
-                    element_name: &String, required_quantity: u64) -> u64 {
+                    element_name: &str, required_quantity: u64) -> Option<u64> {
     if element_name == "ORE" {
-        return required_quantity;
+        return Some(required_quantity);
     }
 
-    let ore_required: u64;
+    let mut ore_required = 0;
     let available_quantity = *available_elements.get(element_name).unwrap_or(&0);
 
     if available_quantity >= required_quantity {
-        ore_required = 0;
-        available_elements.insert(element_name.clone(), available_quantity - required_quantity);
+        available_elements.insert(String::from(element_name), available_quantity - required_quantity);
     } else {
-        let element = elements.get(element_name).unwrap();
+        let element = &elements[element_name];
         let quantity_to_produce = required_quantity - available_quantity;
-        let reactions_required = ((quantity_to_produce as f64)/(element.minimum_quanitity_produced as f64)).ceil() as u64;
+        let reactions_required = (quantity_to_produce - 1)/element.minimum_quanitity_produced + 1;
 
         // Compute the amount of ore required for this element
-        ore_required = element.elements_required
-                              .iter()
-                              .map(|d| get_ore_required(&elements, available_elements, &d.1, reactions_required*d.0))
-                              .sum::<u64>();
+        for (quantity, name) in element.elements_required.iter() {
+            let ore = get_ore_required(elements, available_elements, name, reactions_required.checked_mul(*quantity)?)?;
+            ore_required = ore.checked_add(ore_required)?;
+        }
 
         // Store the excess of Ore as available for other future requests
-        available_elements.insert(element_name.clone(), reactions_required*element.minimum_quanitity_produced - quantity_to_produce);
+        available_elements.insert(String::from(element_name), reactions_required.checked_mul(element.minimum_quanitity_produced)? - quantity_to_produce);
     }
 
-    ore_required
+    Some(ore_required)
 }
 
 #[derive(Debug)]
-struct Element {
+pub struct Element {
     name: String,
     minimum_quanitity_produced: u64,
     elements_required: Vec<(u64, String)>
 }
 
 impl Element {
-    fn from(line: &str) -> Self {
-        let mut result = Element {
-            name: String::from(""),
-            minimum_quanitity_produced: 1,
-            elements_required: Vec::new()
-        };
-
+    /// Constructs an element from the reaction producing it, a line like: "7 A, 1 B => 1 C"
+    fn parse(line_number: usize, line: &str) -> Result<Self, ParseError> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(r"((\d+) ([[:alpha:]]+))+").unwrap();
+            static ref RE: Regex = Regex::new(r"^(\d+) ([[:alpha:]]+)$").unwrap();
         }
 
-        let captures = RE.captures_iter(line);
+        // Each chemical is a quantity and a name, like: "7 A"
+        let chemical = |item: &str| -> Result<(u64, String), ParseError> {
+            let item = item.trim();
+            let capture = RE.captures(item)
+                            .ok_or_else(|| ParseError::at(line_number, line, item, &format!("Expected a quantity and a chemical like 7 A, got: {}", item)))?;
+            let quantity_text = capture.get(1).unwrap().as_str();
+            let quantity = parse_token(line_number, line, quantity_text, "a quantity")?;
+            if quantity == 0 {
+                return Err(ParseError::at(line_number, line, quantity_text, "Expected a quantity of at least 1"));
+            }
+            Ok((quantity, String::from(&capture[2])))
+        };
 
-        for capture in captures {
-            result.elements_required.push((capture[2].parse::<u64>().unwrap(), String::from(&capture[3])))
-        }
+        let mut sides = line.splitn(2, "=>");
+        let (inputs, output) = match (sides.next(), sides.next()) {
+            (Some(inputs), Some(output)) => (inputs, output),
+            _ => return Err(ParseError::new(line_number, 1, &format!("Expected a reaction like 7 A, 1 B => 1 C, got: {}", line.trim())))
+        };
 
-        let e = result.elements_required.pop().unwrap();
-        result.minimum_quanitity_produced = e.0;
-        result.name = e.1;
+        let (minimum_quanitity_produced, name) = chemical(output)?;
 
-        result
+        Ok(Element {
+            name,
+            minimum_quanitity_produced,
+            elements_required: inputs.split(',')
+                                     .map(chemical)
+                                     .collect::<Result<Vec<(u64, String)>, ParseError>>()?
+        })
     }
 }
 
@@ -114,22 +191,22 @@ mod tests {
     #[test]
     fn test_a() {
 
-        assert_eq!(process_a("10 ORE => 10 A\n\
+        assert_eq!(process_a(&get_elements("10 ORE => 10 A\n\
         1 ORE => 1 B\n\
         7 A, 1 B => 1 C\n\
         7 A, 1 C => 1 D\n\
         7 A, 1 D => 1 E\n\
-        7 A, 1 E => 1 FUEL"), 31);
+        7 A, 1 E => 1 FUEL").unwrap()), Ok(31));
 
-        assert_eq!(process_a("9 ORE => 2 A\n\
+        assert_eq!(process_a(&get_elements("9 ORE => 2 A\n\
         8 ORE => 3 B\n\
         7 ORE => 5 C\n\
         3 A, 4 B => 1 AB\n\
         5 B, 7 C => 1 BC\n\
         4 C, 1 A => 1 CA\n\
-        2 AB, 3 BC, 4 CA => 1 FUEL"), 165);
+        2 AB, 3 BC, 4 CA => 1 FUEL").unwrap()), Ok(165));
 
-        assert_eq!(process_a("157 ORE => 5 NZVS\n\
+        assert_eq!(process_a(&get_elements("157 ORE => 5 NZVS\n\
         165 ORE => 6 DCFZ\n\
         44 XJWVT, 5 KHKGT, 1 QDVJ, 29 NZVS, 9 GPVTF, 48 HKGWZ => 1 FUEL\n\
         12 HKGWZ, 1 GPVTF, 8 PSHF => 9 QDVJ\n\
@@ -137,9 +214,9 @@ mod tests {
         177 ORE => 5 HKGWZ\n\
         7 DCFZ, 7 PSHF => 2 XJWVT\n\
         165 ORE => 2 GPVTF\n\
-        3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT"), 13312);
+        3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT").unwrap()), Ok(13312));
 
-        assert_eq!(process_a("2 VPVL, 7 FWMGM, 2 CXFTF, 11 MNCFX => 1 STKFG\n\
+        assert_eq!(process_a(&get_elements("2 VPVL, 7 FWMGM, 2 CXFTF, 11 MNCFX => 1 STKFG\n\
         17 NVRVD, 3 JNWZP => 8 VPVL\n\
         53 STKFG, 6 MNCFX, 46 VJHF, 81 HVMC, 68 CXFTF, 25 GNMV => 1 FUEL\n\
         22 VJHF, 37 MNCFX => 5 FWMGM\n\
@@ -150,9 +227,9 @@ mod tests {
         145 ORE => 6 MNCFX\n\
         1 NVRVD => 8 CXFTF\n\
         1 VJHF, 6 MNCFX => 4 RFSQX\n\
-        176 ORE => 6 VJHF"), 180697);
+        176 ORE => 6 VJHF").unwrap()), Ok(180697));
 
-        assert_eq!(process_a("171 ORE => 8 CNZTR\n\
+        assert_eq!(process_a(&get_elements("171 ORE => 8 CNZTR\n\
         7 ZLQW, 3 BMBT, 9 XCVML, 26 XMNCP, 1 WPTQ, 2 MZWV, 1 RJRHP => 4 PLWSL\n\
         114 ORE => 4 BHXH\n\
         14 VRPVC => 6 BMBT\n\
@@ -168,13 +245,26 @@ mod tests {
         3 BHXH, 2 VRPVC => 7 MZWV\n\
         121 ORE => 7 VRPVC\n\
         7 XCVML => 6 RJRHP\n\
-        5 BHXH, 4 VRPVC => 5 LTCX"), 2210736);
+        5 BHXH, 4 VRPVC => 5 LTCX").unwrap()), Ok(2210736));
+
+        assert_eq!(get_elements("10 ORE => 10 A\n7 A, 1 B => 1 FUEL").err(), Some(ParseError::new(2, 1, "No reaction produces B")));
+        assert_eq!(get_elements("10 ORE => 10 A\n7 A -> 1 FUEL").err(), Some(ParseError::new(2, 1, "Expected a reaction like 7 A, 1 B => 1 C, got: 7 A -> 1 FUEL")));
+        assert_eq!(get_elements("10 ORE => 10 A\n7 A, B => 1 FUEL").err(), Some(ParseError::new(2, 6, "Expected a quantity and a chemical like 7 A, got: B")));
+        assert_eq!(get_elements("10 ORE => 0 A\n7 A => 1 FUEL").err(), Some(ParseError::new(1, 11, "Expected a quantity of at least 1")));
+        assert_eq!(get_elements("10 ORE => 1 A\n1 ORE => 1 A\n7 A => 1 FUEL").err(), Some(ParseError::new(2, 1, "More than one reaction produces A")));
+        assert_eq!(get_elements("1 ORE, 1 B => 1 A\n1 A => 1 B\n7 A => 1 FUEL").err(),
+                   Some(ParseError::new(1, 1, "A can not be produced from ORE, its reactions go round in a circle")));
+        assert_eq!(process_a(&get_elements(&format!("{} ORE => 1 A\n{} A => 1 FUEL", u64::MAX, 2)).unwrap()),
+                   Err(String::from("The ore needed doesn't fit in 64 bits")));
     }
 
     #[test]
     fn test_b() {
+        // A unit of ore yields ten units of fuel, more fuel than ore then
+        assert_eq!(process_b(&get_elements("1 ORE => 10 A\n1 A => 1 FUEL").unwrap()), Ok(10000000000000));
+
         // The 13312 ORE-per-FUEL example could produce 82892753 FUEL
-        assert_eq!(process_b("157 ORE => 5 NZVS\n\
+        assert_eq!(process_b(&get_elements("157 ORE => 5 NZVS\n\
         165 ORE => 6 DCFZ\n\
         44 XJWVT, 5 KHKGT, 1 QDVJ, 29 NZVS, 9 GPVTF, 48 HKGWZ => 1 FUEL\n\
         12 HKGWZ, 1 GPVTF, 8 PSHF => 9 QDVJ\n\
@@ -182,10 +272,10 @@ mod tests {
         177 ORE => 5 HKGWZ\n\
         7 DCFZ, 7 PSHF => 2 XJWVT\n\
         165 ORE => 2 GPVTF\n\
-        3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT"), 82892753);
+        3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT").unwrap()), Ok(82892753));
 
         // The 180697 ORE-per-FUEL example could produce 5586022 FUEL
-        assert_eq!(process_b("2 VPVL, 7 FWMGM, 2 CXFTF, 11 MNCFX => 1 STKFG\n\
+        assert_eq!(process_b(&get_elements("2 VPVL, 7 FWMGM, 2 CXFTF, 11 MNCFX => 1 STKFG\n\
         17 NVRVD, 3 JNWZP => 8 VPVL\n\
         53 STKFG, 6 MNCFX, 46 VJHF, 81 HVMC, 68 CXFTF, 25 GNMV => 1 FUEL\n\
         22 VJHF, 37 MNCFX => 5 FWMGM\n\
@@ -196,10 +286,10 @@ mod tests {
         145 ORE => 6 MNCFX\n\
         1 NVRVD => 8 CXFTF\n\
         1 VJHF, 6 MNCFX => 4 RFSQX\n\
-        176 ORE => 6 VJHF"), 5586022);
+        176 ORE => 6 VJHF").unwrap()), Ok(5586022));
 
         // The 2210736 ORE-per-FUEL example could produce 460664 FUEL
-        assert_eq!(process_b("171 ORE => 8 CNZTR\n\
+        assert_eq!(process_b(&get_elements("171 ORE => 8 CNZTR\n\
         7 ZLQW, 3 BMBT, 9 XCVML, 26 XMNCP, 1 WPTQ, 2 MZWV, 1 RJRHP => 4 PLWSL\n\
         114 ORE => 4 BHXH\n\
         14 VRPVC => 6 BMBT\n\
@@ -215,6 +305,6 @@ mod tests {
         3 BHXH, 2 VRPVC => 7 MZWV\n\
         121 ORE => 7 VRPVC\n\
         7 XCVML => 6 RJRHP\n\
-        5 BHXH, 4 VRPVC => 5 LTCX"), 460664);
+        5 BHXH, 4 VRPVC => 5 LTCX").unwrap()), Ok(460664));
     }
 }
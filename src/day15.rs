@@ -1,7 +1,36 @@
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::collections::VecDeque;
+use std::sync::Mutex;
 
 use super::intcode_computer::*;
+use super::intcode_loader::*;
+use super::parallel_search::*;
+use super::parse_error::*;
+use super::solution::*;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 15;
+
+    type Input = Program;
+    type Params = ();
+    type AnswerA = isize;
+    type AnswerB = usize;
+
+    fn parse(text: &str) -> Result<Program, ParseError> {
+        parse_program(text)
+    }
+
+    fn part_a(input: &Program, _params: &()) -> Result<isize, String> {
+        process_a(input)
+    }
+
+    fn part_b(input: &Program, _params: &()) -> Result<usize, String> {
+        process_b(input)
+    }
+}
 
 type Pair = (isize, isize);
 
@@ -14,8 +43,10 @@ const MOVE_BLOCKED: isize = 0;
 const MOVE_SUCCEEDED: isize = 1;
 const MOVE_FOUND_OXYGEN: isize = 2;
 
-pub fn process_a(instructions: &str) -> isize {
-    let computer = IntCodeComputer::from(instructions);
+const NO_OXYGEN: &str = "The droid explored the whole maze without finding the Oxygen system";
+
+pub fn process_a(program: &Program) -> Result<isize, String> {
+    let computer = IntCodeComputer::from(program);
     let mut visited_locations: HashMap<Pair, isize> = HashMap::new();
     let mut locations_to_visit: VecDeque<(Pair, IntCodeComputer, isize)> = VecDeque::new();
 
@@ -40,7 +71,7 @@ pub fn process_a(instructions: &str) -> isize {
                     let output = next_computer.pop_output();
                     visited_locations.insert(next_position, output);
                     match output {
-                        MOVE_FOUND_OXYGEN => return commands_taken as isize + 1,
+                        MOVE_FOUND_OXYGEN => return Ok(commands_taken as isize + 1),
                         MOVE_SUCCEEDED => locations_to_visit.push_back((next_position,
                                                                         next_computer,
                                                                         commands_taken + 1)),
@@ -51,14 +82,16 @@ pub fn process_a(instructions: &str) -> isize {
         }
     }
 
-    // Return -1 if Oxygen system is not found
-    -1
+    Err(String::from(NO_OXYGEN))
 }
 
-pub fn process_b(instructions: &str) -> usize {
-    let room_graph = get_room_graph(instructions);
+pub fn process_b(program: &Program) -> Result<usize, String> {
+    let room_graph = get_room_graph(program);
 
-    let oxygen = *room_graph.iter().find(|&(_, v)| *v == MOVE_FOUND_OXYGEN).unwrap().0;
+    let oxygen = *room_graph.iter()
+                            .find(|&(_, v)| *v == MOVE_FOUND_OXYGEN)
+                            .ok_or_else(|| String::from(NO_OXYGEN))?
+                            .0;
     let mut visited_locations: HashMap<Pair, usize> = HashMap::new();
     let mut locations_to_visit: VecDeque<(Pair, usize)> = VecDeque::new();
 
@@ -80,10 +113,11 @@ pub fn process_b(instructions: &str) -> usize {
         }
     }
 
-    // Total time taken is just the maximum time reached during the search
-    *visited_locations.values()
-                      .max()
-                      .unwrap()
+    // Total time taken is just the maximum time reached during the search,
+    // a closed-off Oxygen system fills nothing
+    Ok(*visited_locations.values()
+                         .max()
+                         .unwrap_or(&0))
 }
 
 fn move_to(p: &Pair, direction: usize) -> Pair{
@@ -96,44 +130,68 @@ fn move_to(p: &Pair, direction: usize) -> Pair{
     }
 }
 
-fn get_room_graph(instructions: &str) -> HashMap<Pair, isize> {
-    let computer = IntCodeComputer::from(instructions);
-    let mut visited_locations: HashMap<Pair, isize> = HashMap::new();
-    let mut locations_to_visit: VecDeque<(Pair, IntCodeComputer)> = VecDeque::new();
-
-    locations_to_visit.push_back(((0, 0), computer.clone()));
-    visited_locations.insert((0, 0), MOVE_SUCCEEDED);
+fn get_room_graph(program: &Program) -> HashMap<Pair, isize> {
+    let computer = IntCodeComputer::from(program);
+    let visited_locations: Mutex<HashMap<Pair, isize>> = Mutex::new(HashMap::new());
 
-    // Simple breadth first search to populate the graph
-    while !locations_to_visit.is_empty() {
-        let (p, test_computer) = locations_to_visit.pop_front().unwrap();
+    visited_locations.lock().unwrap().insert((0, 0), MOVE_SUCCEEDED);
 
+    // Explores the whole maze, each path with its own computer, on all cores.
+    // Nothing to score: the graph is all we want.
+    parallel_search(vec![((0, 0), computer)], available_threads(), |(p, test_computer), locations_to_visit| {
         // command index, For each of the direction...
         for direction in 1..5 {
             let next_position = move_to(&p, direction);
 
-            if !visited_locations.contains_key(&next_position) {
+            // Claim the location before running the computer, so that no other thread goes there too
+            match visited_locations.lock().unwrap().entry(next_position) {
+                Entry::Occupied(_) => continue,
+                Entry::Vacant(location) => { location.insert(MOVE_BLOCKED); }
+            }
 
-                let mut next_computer = test_computer.clone();
-                next_computer.push_input(direction as isize);
-                next_computer.process(ReturnEvent::InputNeededEvent);
+            let mut next_computer = test_computer.clone();
+            next_computer.push_input(direction as isize);
+            next_computer.process(ReturnEvent::InputNeededEvent);
 
-                if next_computer.has_output() {
-                    let output = next_computer.pop_output();
+            if next_computer.has_output() {
+                let output = next_computer.pop_output();
 
-                    visited_locations.insert(next_position, output);
+                visited_locations.lock().unwrap().insert(next_position, output);
 
-                    match output {
-                        MOVE_FOUND_OXYGEN | MOVE_SUCCEEDED => locations_to_visit.push_back((next_position,
-                                                                                            next_computer)),
-                        MOVE_BLOCKED | _ => ()
-                    }
+                match output {
+                    MOVE_FOUND_OXYGEN | MOVE_SUCCEEDED => locations_to_visit.push((next_position, next_computer)),
+                    _ => ()
                 }
             }
         }
-    }
 
-    visited_locations
+        None
+    }, |&()| ());
+
+    visited_locations.into_inner().unwrap()
 }
 
-// No unit tests available today either :(
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode_recording::*;
+
+    #[test]
+    fn test_oxygen_path_replay() {
+        let instructions = std::fs::read_to_string("testdata/day15/input.txt").unwrap();
+        let recording = Recording::parse(&std::fs::read_to_string("testdata/day15/oxygen_path.rec").unwrap()).unwrap();
+
+        assert_eq!(replay(&mut IntCodeComputer::from(instructions.as_str()), &recording), Ok(()));
+        assert_eq!(recording.inputs().len(), 262);
+        assert_eq!(recording.outputs().last(), Some(&MOVE_FOUND_OXYGEN));
+    }
+
+    #[test]
+    fn test_no_oxygen() {
+        // A droid boxed in by walls on all four sides
+        let program = parse_program("3,20,104,0,1105,1,0").unwrap();
+
+        assert_eq!(process_a(&program), Err(String::from(NO_OXYGEN)));
+        assert_eq!(process_b(&program), Err(String::from(NO_OXYGEN)));
+    }
+}
\ No newline at end of file
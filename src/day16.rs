@@ -1,21 +1,87 @@
-pub fn process_a(text: &str, phases: usize) -> String {
-    cleanup_signal(text.trim(), phases)[0..8].to_string()
+use super::parse_error::*;
+use super::solution::*;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: usize = 16;
+
+    type Input = Vec<isize>;
+    type Params = Signal;
+    type AnswerA = String;
+    type AnswerB = String;
+
+    fn parse(text: &str) -> Result<Vec<isize>, ParseError> {
+        digits(text)
+    }
+
+    fn part_a(input: &Vec<isize>, params: &Signal) -> Result<String, String> {
+        process_a(input, params.phases)
+    }
+
+    fn part_b(input: &Vec<isize>, params: &Signal) -> Result<String, String> {
+        process_b(input, params.phases)
+    }
+}
+
+/// How many phases of FFT to run
+pub struct Signal {
+    pub phases: usize
+}
+
+impl Default for Signal {
+    fn default() -> Self {
+        Signal { phases: 100 }
+    }
 }
 
-pub fn process_b(text: &str, phases: usize) -> String {
-    let offset = text[..7].parse::<usize>().unwrap();
-    cleanup_signal(&text.trim().repeat(10000), phases)[offset..(offset+8)].to_string()
+impl Params for Signal {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "phases" => self.phases = parse_param(key, value)?,
+            _ => return Err(format!("Unknown parameter: {}", key))
+        }
+
+        Ok(())
+    }
+}
+
+// The signal is a single line of digits, at least a message long
+fn digits(text: &str) -> Result<Vec<isize>, ParseError> {
+    let signal = text.trim();
+    let digits = signal.chars()
+                       .enumerate()
+                       .map(|(column, c)| c.to_digit(10)
+                                           .map(|digit| digit as isize)
+                                           .ok_or_else(|| ParseError::new(1, column + 1, &format!("Expected a digit, got: {}", c))))
+                       .collect::<Result<Vec<isize>, ParseError>>()?;
+
+    if digits.len() < 8 {
+        return Err(ParseError::new(1, 1, &format!("Expected a signal of at least 8 digits, got {} digits", digits.len())));
+    }
+
+    Ok(digits)
 }
 
+pub fn process_a(digits: &[isize], phases: usize) -> Result<String, String> {
+    Ok(cleanup_signal(digits, phases)[0..8].to_string())
+}
+
+pub fn process_b(digits: &[isize], phases: usize) -> Result<String, String> {
+    // The message offset is the first seven digits
+    let offset = digits[..7].iter()
+                            .fold(0, |offset, &digit| 10*offset + digit as usize);
+    if offset + 8 > 10000*digits.len() {
+        return Err(format!("The message offset {} is past the end of the signal's {} digits", offset, 10000*digits.len()));
+    }
 
-fn cleanup_signal(text: &str, phases: usize) -> String {
-    let digits = text.chars()
-                     .map(|i| i.to_string()
-                               .parse::<isize>().unwrap())
-                     .collect::<Vec<isize>>();
+    Ok(cleanup_signal(&digits.repeat(10000), phases)[offset..(offset+8)].to_string())
+}
 
+
+fn cleanup_signal(digits: &[isize], phases: usize) -> String {
     // let base_pattern = [0, 1, 0, -1];
-    let mut current_input = digits;
+    let mut current_input = digits.to_vec();
 
     for _ in  0..phases {
         // The annoying math that makes this work
@@ -65,30 +131,35 @@ mod tests {
     #[test]
     fn test_a() {
 
-        assert_eq!(process_a("12345678", 0), "12345678");
+        assert_eq!(process_a(&digits("12345678").unwrap(), 0).as_deref(), Ok("12345678"));
 
-        assert_eq!(process_a("12345678", 1), "48226158");
+        assert_eq!(process_a(&digits("12345678").unwrap(), 1).as_deref(), Ok("48226158"));
 
-        assert_eq!(process_a("12345678", 2), "34040438");
+        assert_eq!(process_a(&digits("12345678").unwrap(), 2).as_deref(), Ok("34040438"));
 
-        assert_eq!(process_a("12345678", 3), "03415518");
+        assert_eq!(process_a(&digits("12345678").unwrap(), 3).as_deref(), Ok("03415518"));
 
-        assert_eq!(process_a("12345678", 4), "01029498");
+        assert_eq!(process_a(&digits("12345678").unwrap(), 4).as_deref(), Ok("01029498"));
 
         // Here are the first eight digits of the final output list after 100 phases for some larger inputs
-        assert_eq!(process_a("80871224585914546619083218645595", 100), "24176176");
+        assert_eq!(process_a(&digits("80871224585914546619083218645595").unwrap(), 100).as_deref(), Ok("24176176"));
+
+        assert_eq!(process_a(&digits("19617804207202209144916044189917").unwrap(), 100).as_deref(), Ok("73745418"));
 
-        assert_eq!(process_a("19617804207202209144916044189917", 100), "73745418");
+        assert_eq!(process_a(&digits("69317163492948606335995924319873").unwrap(), 100).as_deref(), Ok("52432133"));
 
-        assert_eq!(process_a("69317163492948606335995924319873", 100), "52432133");
+        assert_eq!(digits("1234567"), Err(ParseError::new(1, 1, "Expected a signal of at least 8 digits, got 7 digits")));
+        assert_eq!(digits("1234-5678"), Err(ParseError::new(1, 5, "Expected a digit, got: -")));
     }
 
     #[test]
     fn test_b() {
-        assert_eq!(process_b("03036732577212944063491565474664", 100), "84462026");
+        assert_eq!(process_b(&digits("03036732577212944063491565474664").unwrap(), 100), Ok(String::from("84462026")));
+
+        assert_eq!(process_b(&digits("02935109699940807407585447034323").unwrap(), 100), Ok(String::from("78725270")));
 
-        assert_eq!(process_b("02935109699940807407585447034323", 100), "78725270");
+        assert_eq!(process_b(&digits("03081770884921959731165446850517").unwrap(), 100), Ok(String::from("53553731")));
 
-        assert_eq!(process_b("03081770884921959731165446850517", 100), "53553731");
+        assert_eq!(process_b(&digits("98765432").unwrap(), 1), Err(String::from("The message offset 9876543 is past the end of the signal's 80000 digits")));
     }
 }
\ No newline at end of file
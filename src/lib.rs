@@ -4,22 +4,80 @@
 #[macro_use]
 extern crate lazy_static;
 
+#[path = "common/answers.rs"]
+pub mod answers;
+
+#[path = "common/examples.rs"]
+pub mod examples;
+
 #[path = "common/intcode_computer.rs"]
 pub mod intcode_computer;
 
-pub mod day01;
-pub mod day02;
-pub mod day03;
-pub mod day04;
-pub mod day05;
-pub mod day06;
-pub mod day07;
-pub mod day08;
-pub mod day09;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
\ No newline at end of file
+#[path = "common/intcode_ascii.rs"]
+pub mod intcode_ascii;
+
+#[path = "common/intcode_async.rs"]
+pub mod intcode_async;
+
+#[path = "common/intcode_cycles.rs"]
+pub mod intcode_cycles;
+
+#[path = "common/intcode_debugger.rs"]
+pub mod intcode_debugger;
+
+#[path = "common/intcode_devices.rs"]
+pub mod intcode_devices;
+
+#[path = "common/intcode_host_calls.rs"]
+pub mod intcode_host_calls;
+
+#[path = "common/intcode_inspect.rs"]
+pub mod intcode_inspect;
+
+#[path = "common/intcode_loader.rs"]
+pub mod intcode_loader;
+
+#[path = "common/intcode_patch.rs"]
+pub mod intcode_patch;
+
+#[path = "common/intcode_recording.rs"]
+pub mod intcode_recording;
+
+#[path = "common/intcode_trace.rs"]
+pub mod intcode_trace;
+
+#[path = "common/json.rs"]
+pub mod json;
+
+#[path = "common/line_editor.rs"]
+pub mod line_editor;
+
+#[path = "common/parallel_search.rs"]
+pub mod parallel_search;
+
+#[path = "common/parse_error.rs"]
+pub mod parse_error;
+
+#[path = "common/scaffold.rs"]
+pub mod scaffold;
+
+#[path = "common/solution.rs"]
+pub mod solution;
+
+#[path = "common/watch.rs"]
+pub mod watch;
+
+// Declares every day's module and puts its Solver in the registry, so adding a day only takes adding it here
+macro_rules! days {
+    ($($day:ident),*) => {
+        $(pub mod $day;)*
+
+        /// Every day's solution, in order
+        pub fn solutions() -> Vec<Box<dyn solution::Runner>> {
+            vec![$(solution::runner::<$day::Solver>()),*]
+        }
+    };
+}
+
+days!(day01, day02, day03, day04, day05, day06, day07, day08,
+      day09, day10, day11, day12, day13, day14, day15, day16);
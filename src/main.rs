@@ -1,115 +1,585 @@
 use adventofcode_2019::*;
+use adventofcode_2019::answers::*;
+use adventofcode_2019::json::*;
+use adventofcode_2019::parallel_search::*;
+use adventofcode_2019::scaffold::*;
+use adventofcode_2019::solution::*;
+use adventofcode_2019::watch::*;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-fn testdata(path: &str) -> String {
-    use std::fs::File;
-    use std::io::*;
-    let mut file = File::open(path).expect("Unable to open file");
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).expect("Unable to read file");
-    contents.trim().to_string()
+fn read_file(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map(|text| text.trim().to_string())
+                                 .map_err(|error| format!("Unable to read {}: {}", path, error))
+}
+
+fn usage() {
+    let name = std::env::args().next().unwrap();
+    println!("Useage: {} [day] [--part a|b] [--input <file>|-] [--param <key>=<value>]... [--format text|plain|tsv|json] [--parallel]", name);
+    println!("       {} bench [day] [--runs <count>] [same options but --parallel]", name);
+    println!("       {} verify [day] [--answers <file>] [same options]", name);
+    println!("       {} watch <day> [--tests] [--interval <ms>] [same options but --parallel]", name);
+    println!("       {} new-day <day>", name);
+    println!("  bench    Runs each part --runs times (5 by default) and reports min/median/max time and the parse/solve split");
+    println!("  verify   Checks the answers against the known ones, in testdata/answers.toml by default");
+    println!("  watch    Runs the day again whenever a file in its testdata directory, or its --input, changes.");
+    println!("           --tests also runs its tests with cargo test, and again when src/dayNN.rs changes.");
+    println!("           Changes are looked for every --interval milliseconds, 1000 by default");
+    println!("  new-day  Creates the day's source, testdata and problem statement files and registers it in src/lib.rs");
+    println!("  day      Runs only that day, every day runs otherwise");
+    println!("  --part   Runs only that part");
+    println!("  --input  Reads the input from the file, or from stdin with -, instead of testdata");
+    println!("  --param  Overrides one of the day's parameters, like --param width=25 for day 8");
+    println!("  --format text prints \"Day N result a = ...\", plain prints only the answers,");
+    println!("           tsv and json print the answers along with the time they took and the input's hash");
+    println!("  --parallel Runs the days on all cores, still printing them in order, and how long it took compared to");
+    println!("           running them one after the other");
+    println!("--input and --param need a day.");
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Plain,
+    Tsv,
+    Json
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    Run,
+    Bench { runs: usize },
+    Verify { answers: String },
+    Watch { tests: bool, interval: Duration },
+    NewDay
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    command: Command,
+    day: Option<usize>,
+    parts: Vec<Part>,
+    input: Option<String>,
+    params: Vec<(String, String)>,
+    format: Format,
+    parallel: bool
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options { command: Command::Run, day: None, parts: vec![Part::A, Part::B], input: None, params: Vec::new(), format: Format::Text, parallel: false };
+    let mut args = args.iter().peekable();
+
+    match args.peek().map(|arg| arg.as_str()) {
+        Some("bench") => options.command = Command::Bench { runs: 5 },
+        Some("verify") => options.command = Command::Verify { answers: String::from("testdata/answers.toml") },
+        Some("watch") => options.command = Command::Watch { tests: false, interval: Duration::from_millis(1000) },
+        Some("new-day") => options.command = Command::NewDay,
+        _ => ()
+    }
+
+    if options.command != Command::Run {
+        args.next();
+    }
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+        match arg.as_ref() {
+            "--part" => options.parts = match value()?.as_ref() {
+                "a" => vec![Part::A],
+                "b" => vec![Part::B],
+                part => return Err(format!("Unknown part: {}", part))
+            },
+            "--input" => options.input = Some(value()?.clone()),
+            "--param" => {
+                let param = value()?;
+                let mut items = param.splitn(2, '=');
+                match (items.next(), items.next()) {
+                    (Some(key), Some(value)) => options.params.push((String::from(key.trim()), String::from(value))),
+                    _ => return Err(format!("Expected --param <key>=<value>, got: {}", param))
+                }
+            },
+            "--format" => options.format = match value()?.as_ref() {
+                "text" => Format::Text,
+                "plain" => Format::Plain,
+                "tsv" => Format::Tsv,
+                "json" => Format::Json,
+                format => return Err(format!("Unknown format: {}", format))
+            },
+            "--runs" if matches!(options.command, Command::Bench { .. }) => {
+                let runs = value()?;
+                match runs.parse::<usize>() {
+                    Ok(runs) if runs > 0 => options.command = Command::Bench { runs },
+                    _ => return Err(format!("Expected a positive number of runs, got: {}", runs))
+                }
+            },
+            "--answers" if matches!(options.command, Command::Verify { .. }) => options.command = Command::Verify { answers: value()?.clone() },
+            "--tests" => match &mut options.command {
+                Command::Watch { tests, .. } => *tests = true,
+                _ => return Err(format!("Unexpected argument: {}", arg))
+            },
+            "--interval" if matches!(options.command, Command::Watch { .. }) => {
+                let milliseconds = value()?;
+                match (milliseconds.parse::<u64>(), &mut options.command) {
+                    (Ok(milliseconds), Command::Watch { interval, .. }) if milliseconds > 0 => *interval = Duration::from_millis(milliseconds),
+                    _ => return Err(format!("Expected a positive number of milliseconds, got: {}", milliseconds))
+                }
+            },
+            // Bench's timings would only tell how busy the other cores were, and watch only runs one day
+            "--parallel" if !matches!(options.command, Command::Bench { .. } | Command::Watch { .. }) => options.parallel = true,
+            _ if options.day.is_none() && !arg.starts_with("--") => {
+                options.day = Some(arg.parse::<usize>().map_err(|_| format!("Expected a day number, got: {}", arg))?);
+            },
+            _ => return Err(format!("Unexpected argument: {}", arg))
+        }
+    }
+
+    match options.command {
+        Command::NewDay if options.day.is_none() => return Err(String::from("new-day needs a day")),
+        Command::Watch { .. } if options.day.is_none() => return Err(String::from("watch needs a day")),
+        Command::Watch { .. } if options.input.as_deref() == Some("-") => return Err(String::from("watch can't read the input from stdin")),
+        _ => ()
+    }
+
+    if options.day.is_none() && (options.input.is_some() || !options.params.is_empty()) {
+        return Err(String::from("--input and --param need a day"));
+    }
+
+    Ok(options)
+}
+
+fn read_input(solution: &dyn Runner, input: Option<&str>) -> Result<String, String> {
+    use std::io::Read;
+
+    match input {
+        Some("-") => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text).map_err(|error| format!("Unable to read stdin: {}", error))?;
+            Ok(text.trim().to_string())
+        },
+        Some(path) => read_file(path),
+        None => read_file(&solution.input_path())
+    }
 }
 
 fn main() {
-    let days = [day01, day02, day03, day04, day05, day06, day07, day08, day09, day10,
-                day11, day12, day13, day14, day15, day16];
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            println!("{}", message);
+            usage();
+            return;
+        }
+    };
 
-    if std::env::args().count() > 2 {
-        println!("Useage: {} [day number]", std::env::args().nth(0).unwrap());
+    if options.command == Command::NewDay {
+        let day = options.day.unwrap();
+        match create_day(std::path::Path::new("."), day) {
+            Ok(files) => {
+                for file in files {
+                    println!("Wrote {}", file.display());
+                }
+                println!("Day {} is ready, its puzzle input goes in testdata/day{:02}/input.txt", day, day);
+            },
+            Err(message) => println!("Unable to create day {}: {}", day, message)
+        }
         return;
     }
 
-    let day = if std::env::args().count() == 1 { days.len() + 1 }
-              else { std::env::args().nth(1).unwrap().parse::<usize>().expect("Unsigned Integer expected") };
+    let solutions = solutions();
+    let selected = solutions.iter()
+                            .map(|solution| solution.as_ref())
+                            .filter(|solution| options.day.map_or(true, |day| solution.day() == day))
+                            .collect::<Vec<&dyn Runner>>();
 
+    if selected.is_empty() {
+        println!("No solution for day {}", options.day.unwrap());
+        return;
+    }
 
-    if day > 0 && day <= days.len() {
-        days[day - 1]();
+    match &options.command {
+        Command::Bench { runs } => {
+            bench(&selected, &options, *runs);
+            return;
+        },
+        Command::Verify { answers } => {
+            // Failures make for a failed exit code, for scripts
+            if !verify(&selected, &options, answers) {
+                std::process::exit(1);
+            }
+            return;
+        },
+        Command::Watch { tests, interval } => watch(selected[0], &options, *tests, *interval),
+        // new-day is done before looking for the day's solution, which doesn't exist yet
+        Command::Run | Command::NewDay => ()
     }
-    else {
-        for &day in days.iter() {
-            day();
+
+    if options.format == Format::Tsv {
+        println!("day\tpart\tanswer\tduration_ms\tinput_hash\terror");
+    }
+
+    // JSON is printed as a single array once everything ran
+    let mut json_results = Vec::new();
+
+    run_days(&selected, &options, |results| {
+        for result in results {
+            match options.format {
+                Format::Json => json_results.push(result.to_json()),
+                format => result.print(format)
+            }
         }
+    });
+
+    if options.format == Format::Json {
+        println!("{}", JsonValue::Array(json_results));
     }
 }
 
-fn day01() {
-    println!("Day 1 result a = {}", day01::process_a(testdata("testdata/day01/a.txt").as_ref()));
-    println!("Day 1 result b = {}", day01::process_b(testdata("testdata/day01/b.txt").as_ref()));
+struct RunResult {
+    day: usize,
+    part: Part,
+    input_hash: String,
+    outcome: Result<TimedAnswer, String>
 }
 
-fn day02() {
-    println!("Day 2 result a = {}", day02::process_a(testdata("testdata/day02/input.txt").as_ref()));
-    println!("Day 2 result b = {}", day02::process_b(testdata("testdata/day02/input.txt").as_ref()));
-}
+impl RunResult {
+    fn print(&self, format: Format) {
+        match (&self.outcome, format) {
+            (Ok(timed), Format::Plain) => println!("{}", timed.answer),
+            (Ok(timed), Format::Tsv) => println!("{}\t{}\t{}\t{}\t{}\t", self.day, self.part, escape_tsv(&timed.answer),
+                                                 milliseconds(timed.parse + timed.solve), self.input_hash),
+            (Err(message), Format::Tsv) => println!("{}\t{}\t\t\t{}\t{}", self.day, self.part, self.input_hash, escape_tsv(message)),
+            (Ok(timed), _) => println!("Day {} result {} = {}", self.day, self.part, timed.answer),
+            (Err(message), _) => println!("Day {} part {} failed: {}", self.day, self.part, message)
+        }
+    }
 
-fn day03() {
-    println!("Day 3 result a = {}", day03::process_a(testdata("testdata/day03/input.txt").as_ref()));
-    println!("Day 3 result b = {}", day03::process_b(testdata("testdata/day03/input.txt").as_ref()));
-}
+    fn to_json(&self) -> JsonValue {
+        let mut fields = vec![("day", self.day.into()),
+                              ("part", self.part.to_string().as_str().into()),
+                              ("input_hash", self.input_hash.as_str().into())];
 
-fn day04() {
-    println!("Day 4 result a = {}", day04::process_a(136818,685979));
-    println!("Day 4 result b = {}", day04::process_b(136818,685979));
-}
+        match &self.outcome {
+            Ok(timed) => {
+                fields.push(("answer", timed.answer.as_str().into()));
+                fields.push(("duration_ms", JsonValue::Float((timed.parse + timed.solve).as_secs_f64()*1000.0)));
+            },
+            Err(message) => fields.push(("error", message.as_str().into()))
+        }
 
-fn day05() {
-    println!("Day 5 result a = {}", day05::process_a(testdata("testdata/day05/input.txt").as_ref()));
-    println!("Day 5 result b = {}", day05::process_b(testdata("testdata/day05/input.txt").as_ref()));
+        JsonValue::object(fields)
+    }
 }
 
-fn day06() {
-    println!("Day 6 result a = {}", day06::process_a(testdata("testdata/day06/input.txt").as_ref()));
-    println!("Day 6 result b = {}", day06::process_b(testdata("testdata/day06/input.txt").as_ref()));
+// Multi line answers like day 8's image would break the table
+fn escape_tsv(text: &str) -> String {
+    text.trim_start_matches('\n')
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
 }
 
-fn day07() {
-    println!("Day 7 result a = {}", day07::process_a(testdata("testdata/day07/input.txt").as_ref()));
-    println!("Day 7 result b = {}", day07::process_b(testdata("testdata/day07/input.txt").as_ref()));
+fn run(solution: &dyn Runner, options: &Options) -> Vec<RunResult> {
+    let text = read_input(solution, options.input.as_deref());
+    let input_hash = text.as_ref().map(|text| input_hash(text)).unwrap_or_default();
+    // The line numbers alone don't tell which input is malformed
+    let input_name = match options.input.as_deref() {
+        Some("-") => String::from("stdin"),
+        Some(path) => String::from(path),
+        None => solution.input_path()
+    };
+    let describe = |error: RunError| match error {
+        RunError::Parse(error) => format!("Invalid input in {}, {}", input_name, error),
+        error => error.to_string()
+    };
+
+    options.parts.iter()
+                 .map(|&part| RunResult {
+                     day: solution.day(),
+                     part,
+                     input_hash: input_hash.clone(),
+                     outcome: text.clone().and_then(|text| solution.run_timed(&text, part, &options.params).map_err(describe))
+                 })
+                 .collect()
 }
 
-fn day08() {
-    println!("Day 8 result a = {}", day08::process_a(testdata("testdata/day08/input.txt").as_ref(), 25, 6));
-    println!("Day 8 result b = {}", day08::process_b(testdata("testdata/day08/input.txt").as_ref(), 25, 6));
+/// Runs the days one after the other, or all at once with --parallel, handing each day's results to done in day order
+fn run_days(solutions: &[&dyn Runner], options: &Options, mut done: impl FnMut(Vec<RunResult>)) {
+    if !options.parallel {
+        for &solution in solutions {
+            done(run(solution, options));
+        }
+        return;
+    }
+
+    let start = Instant::now();
+    // How long it would have taken one after the other
+    let mut sequential = Duration::default();
+
+    parallel_map_in_order(solutions.to_vec(),
+                          available_threads(),
+                          |solution| {
+                              let start = Instant::now();
+                              let results = run(solution, options);
+                              (results, start.elapsed())
+                          },
+                          |(results, elapsed)| {
+                              sequential += elapsed;
+                              done(results);
+                          });
+
+    // Only where it doesn't get in the way of reading the output
+    if options.format == Format::Text {
+        println!("Ran {} day{} in {} ms, {} ms one after the other", solutions.len(), if solutions.len() == 1 { "" } else { "s" },
+                 milliseconds(start.elapsed()), milliseconds(sequential));
+    }
 }
 
-fn day09() {
-    println!("Day 9 result a = {}", day09::process(testdata("testdata/day09/input.txt").as_ref(), 1));
-    println!("Day 9 result a = {}", day09::process(testdata("testdata/day09/input.txt").as_ref(), 2));
+#[derive(Debug, PartialEq)]
+struct Stats {
+    min: Duration,
+    median: Duration,
+    max: Duration
 }
 
-fn day10() {
-    println!("Day 10 result a = {}", day10::process_a(testdata("testdata/day10/input.txt").as_ref()));
-    // Asteroid at (37, 25) has the maximum visibility of 309 asteroids, as per a
-    // So directly inputting those coordinates, to reduce the burden on process_b
-    println!("Day 10 result a = {}", day10::process_b(testdata("testdata/day10/input.txt").as_ref(), (37, 25), 199));
+impl Stats {
+    fn new(durations: &[Duration]) -> Stats {
+        let mut durations = durations.to_vec();
+        durations.sort();
+
+        Stats {
+            min: durations[0],
+            median: durations[durations.len()/2],
+            max: durations[durations.len() - 1]
+        }
+    }
 }
 
-fn day11() {
-    println!("Day 11 result a = {}", day11::process_a(testdata("testdata/day11/input.txt").as_ref()));
-    println!("Day 11 result b = {}", day11::process_b(testdata("testdata/day11/input.txt").as_ref()));
+fn milliseconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64()*1000.0)
 }
 
-fn day12() {
-    println!("Day 12 result a = {}", day12::process_a(testdata("testdata/day12/input.txt").as_ref(), 1000));
-    println!("Day 12 result b = {}", day12::process_b(testdata("testdata/day12/input.txt").as_ref()));
+fn bench(solutions: &[&dyn Runner], options: &Options, runs: usize) {
+    let mut json_results = Vec::new();
+
+    match options.format {
+        Format::Tsv => println!("day\tpart\truns\tmin_ms\tmedian_ms\tmax_ms\tparse_ms\tsolve_ms"),
+        Format::Json => (),
+        _ => println!("{:>3} {:>4} {:>12} {:>12} {:>12} {:>12} {:>12}", "Day", "Part", "Min (ms)", "Median (ms)", "Max (ms)", "Parse (ms)", "Solve (ms)")
+    }
+
+    for solution in solutions {
+        let text = match read_input(*solution, options.input.as_deref()) {
+            Ok(text) => text,
+            Err(message) => {
+                println!("Day {} failed: {}", solution.day(), message);
+                continue;
+            }
+        };
+
+        for &part in options.parts.iter() {
+            let timings = match (0..runs).map(|_| solution.run_timed(&text, part, &options.params))
+                                         .collect::<Result<Vec<TimedAnswer>, RunError>>() {
+                Ok(timings) => timings,
+                Err(message) => {
+                    println!("Day {} part {} failed: {}", solution.day(), part, message);
+                    continue;
+                }
+            };
+
+            let total = Stats::new(&timings.iter().map(|timing| timing.parse + timing.solve).collect::<Vec<Duration>>());
+            // The medians of each, as the split of the median run could be an outlier
+            let parse = Stats::new(&timings.iter().map(|timing| timing.parse).collect::<Vec<Duration>>()).median;
+            let solve = Stats::new(&timings.iter().map(|timing| timing.solve).collect::<Vec<Duration>>()).median;
+
+            let columns = [milliseconds(total.min), milliseconds(total.median), milliseconds(total.max), milliseconds(parse), milliseconds(solve)];
+
+            match options.format {
+                Format::Tsv => println!("{}\t{}\t{}\t{}", solution.day(), part, runs, columns.join("\t")),
+                Format::Json => {
+                    let ms = |duration: Duration| JsonValue::Float(duration.as_secs_f64()*1000.0);
+                    json_results.push(JsonValue::object(vec![("day", solution.day().into()),
+                                                             ("part", part.to_string().as_str().into()),
+                                                             ("runs", runs.into()),
+                                                             ("min_ms", ms(total.min)),
+                                                             ("median_ms", ms(total.median)),
+                                                             ("max_ms", ms(total.max)),
+                                                             ("parse_ms", ms(parse)),
+                                                             ("solve_ms", ms(solve))]));
+                },
+                _ => println!("{:>3} {:>4} {:>12} {:>12} {:>12} {:>12} {:>12}", solution.day(), part.to_string(), columns[0], columns[1], columns[2], columns[3], columns[4])
+            }
+        }
+    }
+
+    if options.format == Format::Json {
+        println!("{}", JsonValue::Array(json_results));
+    }
 }
 
-fn day13() {
-    println!("Day 13 result a = {}", day13::process_a(testdata("testdata/day13/input.txt").as_ref()));
-    println!("Day 13 result b = {}", day13::process_b(testdata("testdata/day13/input.txt").as_ref()));
+/// Runs the day again whenever its input changes, never returns
+fn watch(solution: &dyn Runner, options: &Options, tests: bool, interval: Duration) -> ! {
+    let day = solution.day();
+    let source = PathBuf::from(format!("src/day{:02}.rs", day));
+
+    let input_path = solution.input_path();
+    let mut paths = vec![Path::new(&input_path).parent().map_or_else(|| PathBuf::from(&input_path), Path::to_path_buf)];
+    paths.extend(options.input.iter().map(PathBuf::from));
+    // This binary can't reload its own code, so a change to the source is only worth watching for the tests
+    if tests {
+        paths.push(source.clone());
+    }
+
+    let mut watcher = Watcher::new(paths);
+    let mut changed = Vec::new();
+
+    loop {
+        if changed.is_empty() || changed.iter().any(|path| path != &source) {
+            for result in run(solution, options) {
+                match options.format {
+                    Format::Json => println!("{}", result.to_json()),
+                    format => result.print(format)
+                }
+            }
+        }
+
+        if tests {
+            run_tests(day);
+        }
+
+        println!("Watching day {} for changes...", day);
+        loop {
+            std::thread::sleep(interval);
+            changed = watcher.changed();
+            if !changed.is_empty() {
+                break;
+            }
+        }
+
+        println!("\nChanged: {}", changed.iter().map(|path| path.display().to_string()).collect::<Vec<String>>().join(", "));
+    }
 }
 
-fn day14() {
-    println!("Day 14 result a = {}", day14::process_a(testdata("testdata/day14/input.txt").as_ref()));
-    println!("Day 14 result b = {}", day14::process_b(testdata("testdata/day14/input.txt").as_ref()));
+// Builds and runs the day's unit tests, by their module path
+fn run_tests(day: usize) {
+    match std::process::Command::new("cargo").args(["test", "--lib", &format!("day{:02}::", day)]).status() {
+        Ok(status) if status.success() => println!("Day {} tests passed", day),
+        Ok(_) => println!("Day {} tests failed", day),
+        Err(error) => println!("Unable to run cargo test: {}", error)
+    }
 }
 
-fn day15() {
-    println!("Day 15 result a = {}", day15::process_a(testdata("testdata/day15/input.txt").as_ref()));
-    println!("Day 15 result b = {}", day15::process_b(testdata("testdata/day15/input.txt").as_ref()));
+/// Returns whether none of the answers were wrong
+fn verify(solutions: &[&dyn Runner], options: &Options, answers_path: &str) -> bool {
+    let answers = match std::fs::read_to_string(answers_path).map_err(|error| error.to_string())
+                                                             .and_then(|text| parse_answers(&text).map_err(|error| error.to_string())) {
+        Ok(answers) => answers,
+        Err(message) => {
+            println!("Unable to load {}: {}", answers_path, message);
+            return false;
+        }
+    };
+
+    let (mut passed, mut failed, mut unknown) = (0, 0, Vec::new());
+
+    run_days(solutions, options, |results| {
+        for result in results {
+            let actual = match result.outcome {
+                Ok(timed) => timed.answer,
+                Err(message) => {
+                    println!("Day {} part {}: FAIL ({})", result.day, result.part, message);
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            match check(&answers, result.day, result.part, &result.input_hash, &actual) {
+                Verdict::Pass => {
+                    println!("Day {} part {}: pass", result.day, result.part);
+                    passed += 1;
+                },
+                Verdict::Fail { expected } => {
+                    println!("Day {} part {}: FAIL", result.day, result.part);
+                    println!("{}", describe_diff(&expected, &actual));
+                    failed += 1;
+                },
+                Verdict::Unknown => {
+                    println!("Day {} part {}: unknown", result.day, result.part);
+                    unknown.push(Answer { day: result.day, part: result.part, input_hash: result.input_hash, answer: actual });
+                }
+            }
+        }
+    });
+
+    println!("{} passed, {} failed, {} unknown", passed, failed, unknown.len());
+
+    if !unknown.is_empty() {
+        println!("Once confirmed, the unknown answers can be added to {}:", answers_path);
+        for answer in unknown.iter() {
+            print!("\n{}", format_answer(answer));
+        }
+    }
+
+    failed == 0
 }
 
-fn day16() {
-    println!("Day 16 result a = {}", day16::process_a(testdata("testdata/day16/input.txt").as_ref(), 100));
-    println!("Day 16 result b = {}", day16::process_b(testdata("testdata/day16/input.txt").as_ref(), 100));
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(&args("")), Ok(Options { command: Command::Run, day: None, parts: vec![Part::A, Part::B], input: None, params: Vec::new(), format: Format::Text, parallel: false }));
+        assert_eq!(parse_args(&args("8 --part b --input - --param width=3 --param height=2 --format plain")),
+                   Ok(Options { command: Command::Run,
+                                day: Some(8),
+                                parts: vec![Part::B],
+                                input: Some(String::from("-")),
+                                params: vec![(String::from("width"), String::from("3")), (String::from("height"), String::from("2"))],
+                                format: Format::Plain,
+                                parallel: false }));
+
+        assert_eq!(parse_args(&args("--param steps=10")), Err(String::from("--input and --param need a day")));
+        assert_eq!(parse_args(&args("12 --param steps")), Err(String::from("Expected --param <key>=<value>, got: steps")));
+        assert_eq!(parse_args(&args("12 --part")), Err(String::from("--part needs a value")));
+        assert_eq!(parse_args(&args("twelve")), Err(String::from("Expected a day number, got: twelve")));
+
+        assert_eq!(parse_args(&args("bench 16 --runs 3")).map(|options| (options.command, options.day)), Ok((Command::Bench { runs: 3 }, Some(16))));
+        assert_eq!(parse_args(&args("bench --runs 0")), Err(String::from("Expected a positive number of runs, got: 0")));
+        assert_eq!(parse_args(&args("16 --runs 3")), Err(String::from("Unexpected argument: --runs")));
+        assert_eq!(parse_args(&args("new-day 17")).map(|options| (options.command, options.day)), Ok((Command::NewDay, Some(17))));
+        assert_eq!(parse_args(&args("new-day")), Err(String::from("new-day needs a day")));
+        assert_eq!(parse_args(&args("verify --answers mine.toml")).map(|options| options.command), Ok(Command::Verify { answers: String::from("mine.toml") }));
+        assert_eq!(parse_args(&args("verify --parallel")).map(|options| options.parallel), Ok(true));
+        assert_eq!(parse_args(&args("watch 12 --tests --interval 250")).map(|options| options.command),
+                   Ok(Command::Watch { tests: true, interval: Duration::from_millis(250) }));
+        assert_eq!(parse_args(&args("watch --tests")), Err(String::from("watch needs a day")));
+        assert_eq!(parse_args(&args("watch 12 --input -")), Err(String::from("watch can't read the input from stdin")));
+        assert_eq!(parse_args(&args("12 --tests")), Err(String::from("Unexpected argument: --tests")));
+        assert_eq!(parse_args(&args("bench --parallel")), Err(String::from("Unexpected argument: --parallel")));
+    }
+
+    #[test]
+    fn test_structured_results() {
+        let result = RunResult {
+            day: 8,
+            part: Part::B,
+            input_hash: String::from("00ff"),
+            outcome: Ok(TimedAnswer { answer: String::from("\n# \n #"), parse: Duration::from_millis(1), solve: Duration::from_millis(2) })
+        };
+
+        assert_eq!(result.to_json().to_string(), "{\"day\":8,\"part\":\"b\",\"input_hash\":\"00ff\",\"answer\":\"\\n# \\n #\",\"duration_ms\":3}");
+        assert_eq!(escape_tsv("\n# \n #"), "# \\n #");
+    }
+
+    #[test]
+    fn test_stats() {
+        let durations = [5, 1, 3, 2, 4].iter().map(|&ms| Duration::from_millis(ms)).collect::<Vec<Duration>>();
+        assert_eq!(Stats::new(&durations), Stats { min: Duration::from_millis(1), median: Duration::from_millis(3), max: Duration::from_millis(5) });
+        assert_eq!(milliseconds(Duration::from_micros(1500)), "1.500");
+    }
+}
@@ -0,0 +1,28 @@
+// Runs the annotated examples of every day's problem statement through that day's solution
+use adventofcode_2019::examples::*;
+use adventofcode_2019::solutions;
+
+#[test]
+fn test_examples() {
+    let mut failures = Vec::new();
+    let mut count = 0;
+
+    for solution in solutions() {
+        let path = format!("problem_statements/day{:02}.txt", solution.day());
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) => continue
+        };
+
+        let examples = parse_examples(&text).unwrap_or_else(|error| panic!("{}: {}", path, error));
+        for example in examples.iter() {
+            if let Err(message) = check_example(solution.as_ref(), example) {
+                failures.push(message);
+            }
+        }
+        count += examples.len();
+    }
+
+    assert!(count > 0, "No examples found in problem_statements");
+    assert!(failures.is_empty(), "{} of {} examples failed:\n{}", failures.len(), count, failures.join("\n"));
+}